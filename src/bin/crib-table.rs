@@ -0,0 +1,33 @@
+// write the expected crib value of every two card discard to a table
+
+use cribbage::crib_table::CribTable;
+use cribbage::score::ScoringRules;
+
+use clap::Parser;
+use std::fs::File;
+use std::io::BufWriter;
+
+/// write a table of the average points each two card discard is worth
+/// in the crib, to be loaded by `cribbage::crib_table::CribTable::load`
+#[derive(Parser,Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// output file to write the table to
+    #[clap(short, long, value_parser)]
+    output_path : String,
+
+    /// number of opponent hands to sample for each discard
+    /// when the opponent discards optimally, at least one
+    #[clap(short, long, value_parser = clap::value_parser!(u32).range(1..), default_value_t = 1000)]
+    samples : u32,
+
+    /// seed for sampling the opponent's hands
+    #[clap(long, value_parser, default_value_t = 0)]
+    seed : u64
+}
+
+fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+    let table = CribTable::generate(&ScoringRules::default(), args.samples, args.seed);
+    table.write(BufWriter::new(File::create(args.output_path)?))
+}
@@ -0,0 +1,29 @@
+// write the score of every five-card hand to a compact lookup table
+
+use cribbage::lut;
+use cribbage::score::{ HandKind, ScoringRules };
+
+use clap::Parser;
+use std::fs::File;
+use std::io::BufWriter;
+
+/// write a binary table of the score of every five-card hand,
+/// to be loaded by `cribbage::lut::ScoreTable`
+#[derive(Parser,Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// output file to write the table to
+    #[clap(short, long, value_parser)]
+    output_path : String,
+
+    /// score the hands as the crib instead of a player's hand
+    #[clap(long, action)]
+    crib : bool
+}
+
+fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+    let kind = if args.crib { HandKind::Crib } else { HandKind::Hand };
+    let out = BufWriter::new(File::create(args.output_path)?);
+    lut::write_table(out, &ScoringRules::default(), kind)
+}
@@ -1,6 +1,7 @@
 // calculate points of all five-card hands possible in the deck
 
 use cribbage::deck;
+use cribbage::score::{ HandKind, ScoringRules };
 
 use clap::Parser;
 
@@ -10,30 +11,32 @@ use clap::Parser;
 struct Args {
     /// output file to write data to in CSV format
     #[clap(short, long, value_parser)]
-    output_path : String
+    output_path : String,
+
+    /// score the hands as the crib instead of a player's hand
+    #[clap(long, action)]
+    crib : bool
 }
 
 fn main() -> Result<(), csv::Error> {
     let args = Args::parse();
 
-    // this is the computationally intensive part of this program
-    // but is also the part that is well tested in the library and
-    // so will not be the cause of an error here
-    let lut = deck::unique_scores();
-
-    // simply write it to CSV
+    // the hands are scored as they are written out so nothing
+    // is held in memory and the rows always come out in the same order
+    let kind = if args.crib { HandKind::Crib } else { HandKind::Hand };
     let mut wtr = csv::Writer::from_path(args.output_path)?;
-    wtr.write_record(&["hand0","hand1","hand2","hand3","cut","score"])?;
-    for (hand, score) in lut {
-        wtr.write_record(&[
-                         hand.hand[0].to_string(),
-                         hand.hand[1].to_string(),
-                         hand.hand[2].to_string(),
-                         hand.hand[3].to_string(),
-                         hand.cut.to_string(),
+    wtr.write_record(["hand0","hand1","hand2","hand3","cut","score"])?;
+    for (hand, score) in deck::all_hands_with(&ScoringRules::default(), kind) {
+        wtr.write_record([
+                         hand.hand()[0].to_string(),
+                         hand.hand()[1].to_string(),
+                         hand.hand()[2].to_string(),
+                         hand.hand()[3].to_string(),
+                         hand.cut().to_string(),
                          score.to_string()
         ])?;
     }
+    wtr.flush()?;
 
     Ok(())
 }
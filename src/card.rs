@@ -1,12 +1,94 @@
 use std::{
+    error,
     fmt,
     str,
 };
 use std::str::FromStr;
 use strum_macros::EnumIter;
 
+/// the ways parsing a card (or its rank or suit) can go wrong
+///
+/// each variant carries enough information to tell the user
+/// exactly which part of their input was not understood
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardParseError {
+    /// the input did not have the expected number of characters,
+    /// the number of characters that were found is stored
+    BadLength(usize),
+    /// the character could not be deduced into a rank
+    UnknownRank(char),
+    /// the character could not be deduced into a suit
+    UnknownSuit(char),
+    /// the input contained a character outside of ASCII
+    /// that is not one of the suit symbols
+    NonAscii(char),
+    /// a word in the long form of a card (e.g. "Ten of Hearts")
+    /// was not recognized
+    UnknownWord(String),
+    /// the card starting at character `position` of a larger
+    /// string could not be parsed
+    At {
+        position: usize,
+        error: Box<CardParseError>,
+    },
+}
+
+impl CardParseError {
+    /// attach the position of the card within a larger string
+    #[must_use]
+    pub fn at(self, position: usize) -> Self {
+        Self::At { position, error: Box::new(self) }
+    }
+}
+
+impl fmt::Display for CardParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadLength(n) => write!(f, "expected a rank and a suit but found {} characters", n),
+            Self::UnknownRank(c) => write!(f, "unknown rank '{}'", c),
+            Self::UnknownSuit(c) => write!(f, "unknown suit '{}'", c),
+            Self::NonAscii(c) => write!(f, "unexpected non-ASCII character '{}'", c),
+            Self::UnknownWord(w) => write!(f, "unknown word '{}'", w),
+            Self::At { position, error } => write!(f, "card at position {}: {}", position, error),
+        }
+    }
+}
+
+impl error::Error for CardParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::At { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// pull the only character out of a string
+///
+/// returns `None` if there isn't exactly one character
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// the error for a word that could not be deduced into a rank or suit
+///
+/// single characters are reported by the `unknown` variant
+/// (or as `NonAscii`) while longer words are reported whole
+fn unknown(s: &str, unknown: fn(char) -> CardParseError) -> CardParseError {
+    match single_char(s) {
+        Some(c) if !c.is_ascii() => CardParseError::NonAscii(c),
+        Some(c) => unknown(c),
+        None if s.is_empty() => CardParseError::BadLength(0),
+        None => CardParseError::UnknownWord(s.to_string()),
+    }
+}
+
 /// the four suits a card can have
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, EnumIter)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, EnumIter)]
 pub enum Suit {
     Heart,
     Spade,
@@ -15,51 +97,59 @@ pub enum Suit {
 }
 
 impl FromStr for Suit {
-    type Err = &'static str;
+    type Err = CardParseError;
     /// convert a string into a suit
     ///
     /// the possible strings are the first letter of the
-    /// suit names (H, S, D, and C) case insensitive.
-    ///
-    /// We could think about expanding this to the UTF-8
-    /// symbols for these suits but I don't think that's
-    /// important right now
+    /// suit names (H, S, D, and C), the full suit name
+    /// singular or plural (e.g. "Heart" or "hearts"), or
+    /// the UTF-8 symbol for the suit (filled or not).
+    /// The letters and names are case insensitive.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "H"|"h" => Ok(Self::Heart),
-            "S"|"s" => Ok(Self::Spade),
-            "D"|"d" => Ok(Self::Diamond),
-            "C"|"c" => Ok(Self::Club),
-            _ => Err("Unrecongized suit")
+        match s.to_ascii_lowercase().as_str() {
+            "h"|"♥"|"♡"|"heart"|"hearts" => Ok(Self::Heart),
+            "s"|"♠"|"♤"|"spade"|"spades" => Ok(Self::Spade),
+            "d"|"♦"|"♢"|"diamond"|"diamonds" => Ok(Self::Diamond),
+            "c"|"♣"|"♧"|"club"|"clubs" => Ok(Self::Club),
+            _ => Err(unknown(s, CardParseError::UnknownSuit))
         }
     }
 }
 
 impl fmt::Display for Suit {
     /// convert a suit into its string representation
+    ///
+    /// the alternate form (`{:#}`) uses the UTF-8 symbol
+    /// for the suit instead of its first letter
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", match self {
-            Self::Heart => "H",
-            Self::Spade => "S",
-            Self::Diamond => "D",
-            Self::Club => "C"
+        write!(f, "{}", match (self, f.alternate()) {
+            (Self::Heart, false) => "H",
+            (Self::Spade, false) => "S",
+            (Self::Diamond, false) => "D",
+            (Self::Club, false) => "C",
+            (Self::Heart, true) => "♥",
+            (Self::Spade, true) => "♠",
+            (Self::Diamond, true) => "♦",
+            (Self::Club, true) => "♣"
         })
     }
 }
 
-impl std::hash::Hash for Suit {
-    fn hash<H: std::hash::Hasher>(&self, state : &mut H) {
-        match *self {
-            Suit::Heart => state.write_u8(0),
-            Suit::Spade => state.write_u8(1),
-            Suit::Diamond => state.write_u8(2),
-            Suit::Club => state.write_u8(3),
+impl Suit {
+    /// the plural name of the suit as it would be said aloud
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Heart => "Hearts",
+            Self::Spade => "Spades",
+            Self::Diamond => "Diamonds",
+            Self::Club => "Clubs"
         }
     }
 }
 
 /// the rank a card can have
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, EnumIter)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, EnumIter)]
 pub enum Rank {
     Ace = 0,
     Two,
@@ -77,29 +167,41 @@ pub enum Rank {
 }
 
 impl FromStr for Rank {
-    type Err = &'static str;
+    type Err = CardParseError;
+    /// convert a string into a rank
+    ///
+    /// the possible strings are the single character
+    /// used when printing a rank, "T" or "10" for a Ten,
+    /// or the full name of the rank (e.g. "Queen").
+    /// All of these are case insensitive.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "A" => Ok(Self::Ace),
-            "2" => Ok(Self::Two),
-            "3" => Ok(Self::Three),
-            "4" => Ok(Self::Four),
-            "5" => Ok(Self::Five),
-            "6" => Ok(Self::Six),
-            "7" => Ok(Self::Seven),
-            "8" => Ok(Self::Eight),
-            "9" => Ok(Self::Nine),
-            "0"|"T" => Ok(Self::Ten),
-            "J" => Ok(Self::Jack),
-            "Q" => Ok(Self::Queen),
-            "K" => Ok(Self::King),
-            _ => Err("Unknown rank")
+        match s.to_ascii_lowercase().as_str() {
+            "a"|"ace" => Ok(Self::Ace),
+            "2"|"two" => Ok(Self::Two),
+            "3"|"three" => Ok(Self::Three),
+            "4"|"four" => Ok(Self::Four),
+            "5"|"five" => Ok(Self::Five),
+            "6"|"six" => Ok(Self::Six),
+            "7"|"seven" => Ok(Self::Seven),
+            "8"|"eight" => Ok(Self::Eight),
+            "9"|"nine" => Ok(Self::Nine),
+            "0"|"t"|"10"|"ten" => Ok(Self::Ten),
+            "j"|"jack" => Ok(Self::Jack),
+            "q"|"queen" => Ok(Self::Queen),
+            "k"|"king" => Ok(Self::King),
+            _ => Err(unknown(s, CardParseError::UnknownRank))
         }
     }
 }
 
 impl fmt::Display for Rank {
+    /// convert a rank into its single-character representation
+    ///
+    /// the alternate form (`{:#}`) writes a Ten as "10"
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() && *self == Self::Ten {
+            return write!(f, "10");
+        }
         write!(f, "{}", match self {
             Self::Ace => "A",
             Self::Two => "2",
@@ -118,9 +220,53 @@ impl fmt::Display for Rank {
     }
 }
 
-impl std::hash::Hash for Rank {
-    fn hash<H : std::hash::Hasher>(&self, state: &mut H) {
-        state.write_usize(mask(*self));
+impl Rank {
+    /// the rank directly above this one, `None` for a King
+    ///
+    /// this is the ordering used for runs where Aces are low
+    #[must_use]
+    pub const fn next(self) -> Option<Self> {
+        if mask(self) + 1 < ALL_RANKS.len() {
+            Some(ALL_RANKS[mask(self) + 1])
+        } else {
+            None
+        }
+    }
+
+    /// the rank directly below this one, `None` for an Ace
+    #[must_use]
+    pub const fn prev(self) -> Option<Self> {
+        if mask(self) > 0 {
+            Some(ALL_RANKS[mask(self) - 1])
+        } else {
+            None
+        }
+    }
+
+    /// the value of this rank when counting to fifteen or 31, see [`value`]
+    #[must_use]
+    pub const fn pip_value(self) -> i32 {
+        value(self)
+    }
+
+    /// the name of the rank as it would be said aloud
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Ace => "Ace",
+            Self::Two => "Two",
+            Self::Three => "Three",
+            Self::Four => "Four",
+            Self::Five => "Five",
+            Self::Six => "Six",
+            Self::Seven => "Seven",
+            Self::Eight => "Eight",
+            Self::Nine => "Nine",
+            Self::Ten => "Ten",
+            Self::Jack => "Jack",
+            Self::Queen => "Queen",
+            Self::King => "King"
+        }
     }
 }
 
@@ -133,14 +279,16 @@ impl std::hash::Hash for Rank {
 /// The implementation here is a simple cast so it
 /// depends on the declaration order in the original
 /// enum
-const fn mask(r : Rank) -> usize {
+#[must_use]
+pub const fn mask(r : Rank) -> usize {
     r as usize
 }
 
 /// the value a rank has when calculating fifteens
 ///
 /// all face cards have value 10 and Aces are always one
-const fn value(r : Rank) -> i32 {
+#[must_use]
+pub const fn value(r : Rank) -> i32 {
     match r {
         Rank::Ace => 1,
         Rank::Two => 2,
@@ -155,52 +303,391 @@ const fn value(r : Rank) -> i32 {
     }
 }
 
+/// all four suits in their declaration order
+pub const ALL_SUITS : [Suit; 4] = [
+    Suit::Heart,
+    Suit::Spade,
+    Suit::Diamond,
+    Suit::Club
+];
+
+/// all thirteen ranks from Ace to King
+pub const ALL_RANKS : [Rank; 13] = [
+    Rank::Ace,
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King
+];
+
+/// all 52 cards in the order of their index (see [`Card::index`])
+pub const ALL_CARDS : [Card; 52] = {
+    let mut cards = [Card { suit : Suit::Heart, rank : Rank::Ace }; 52];
+    let mut i = 0;
+    while i < 52 {
+        cards[i] = Card { suit : ALL_SUITS[i % 4], rank : ALL_RANKS[i / 4] };
+        i += 1;
+    }
+    cards
+};
+
 /// a card is a unique combination of suit and rank
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+///
+/// The derived ordering sorts by suit and then rank which is helpful
+/// for keeping a hand in a canonical order but rarely what is wanted
+/// during a game. Use one of [`ByRank`], [`ByValue`], or [`BySuitThenRank`]
+/// to be explicit about which ordering is needed.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
 pub struct Card {
     pub suit: Suit,
     pub rank: Rank,
 }
 
+/// order cards by rank (Ace low, King high)
+///
+/// cards of the same rank are then ordered by suit so
+/// that two different cards are never equal
+///
+/// ```
+/// # use cribbage::{cards, card::ByRank};
+/// let mut hand = cards!("KH 2C 0S");
+/// hand.sort_by_key(|c| ByRank(*c));
+/// assert_eq!(hand, cards!("2C 0S KH"));
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct ByRank(pub Card);
+
+impl Ord for ByRank {
+    fn cmp(&self, other : &Self) -> std::cmp::Ordering {
+        (self.0.rank, self.0.suit).cmp(&(other.0.rank, other.0.suit))
+    }
+}
+
+impl PartialOrd for ByRank {
+    fn partial_cmp(&self, other : &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// order cards by the value they add to the count (face cards are all 10)
+///
+/// cards of the same value are then ordered by rank and suit so
+/// that two different cards are never equal
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct ByValue(pub Card);
+
+impl Ord for ByValue {
+    fn cmp(&self, other : &Self) -> std::cmp::Ordering {
+        (self.0.value(), ByRank(self.0)).cmp(&(other.0.value(), ByRank(other.0)))
+    }
+}
+
+impl PartialOrd for ByValue {
+    fn partial_cmp(&self, other : &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// order cards by suit and then rank within each suit
+///
+/// this is the same as the ordering derived on [`Card`]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct BySuitThenRank(pub Card);
+
+impl Ord for BySuitThenRank {
+    fn cmp(&self, other : &Self) -> std::cmp::Ordering {
+        (self.0.suit, self.0.rank).cmp(&(other.0.suit, other.0.rank))
+    }
+}
+
+impl PartialOrd for BySuitThenRank {
+    fn partial_cmp(&self, other : &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl FromStr for Card {
-    type Err = &'static str;
+    type Err = CardParseError;
+    /// convert a string into a card
+    ///
+    /// There are two forms a card can take.
+    /// - Short: the rank followed by the suit with no space,
+    ///   e.g. "TH", "0h", "10H", or "10♥" for the Ten of Hearts
+    /// - Long: the rank and suit names separated by "of",
+    ///   e.g. "Ten of Hearts" or "ace of spades"
+    ///
+    /// Leading and trailing whitespace is ignored.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.chars().count() != 2 {
-            return Err("Number of characters in `s` != 2");
+        let s = s.trim();
+        let words : Vec<&str> = s.split_whitespace().collect();
+        if words.len() > 1 {
+            return match words[..] {
+                [rank, of, suit] if of.eq_ignore_ascii_case("of") => Ok(Self {
+                    suit : Suit::from_str(suit)?,
+                    rank : Rank::from_str(rank)?
+                }),
+                _ => Err(CardParseError::UnknownWord(s.to_string()))
+            };
+        }
+
+        // we walk the characters rather than slicing the bytes so
+        // that a multi-byte character (like a suit symbol) is
+        // handled instead of panicking on a bad slice boundary
+        let n_chars = s.chars().count();
+        let (split, suit) = match s.char_indices().last() {
+            Some(last) if n_chars == 2 || n_chars == 3 => last,
+            _ => return Err(CardParseError::BadLength(n_chars))
         };
-        // this is not _super_ good Rust and will panic if we get 
-        // some valid UTF-8 string that is two characters but one
-        // "grapheme cluster", I am choosing to ignore this possibility
-        // at the moment (See Section 8.2 of The Book)
-        let the_rank = Rank::from_str(&s[0..1])?;
-        let the_suit = Suit::from_str(&s[1..2])?;
+        let rank = &s[..split];
+        if n_chars == 3 && rank != "10" {
+            return Err(CardParseError::BadLength(n_chars));
+        }
+        let the_rank = Rank::from_str(rank)?;
+        let the_suit = Suit::from_str(&suit.to_string())?;
         Ok(Self { suit : the_suit, rank : the_rank })
     }
 }
 
 impl fmt::Display for Card {
+    /// print the short form of the card
+    ///
+    /// the alternate form (`{:#}`) passes through to the rank
+    /// and suit so that a card is written like "10♥"
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}", self.rank, self.suit)
+        if f.alternate() {
+            write!(f, "{:#}{:#}", self.rank, self.suit)
+        } else {
+            write!(f, "{}{}", self.rank, self.suit)
+        }
     }
 }
 
-impl std::hash::Hash for Card {
-    fn hash<H : std::hash::Hasher>(&self, state: &mut H) {
-        self.suit.hash(state);
-        self.rank.hash(state);
+/// a helper for printing the long name of a card
+///
+/// this is constructed by [`Card::long_name`]
+#[derive(Debug, Clone, Copy)]
+pub struct LongName(Card);
+
+impl fmt::Display for LongName {
+    /// print the card as it would be said aloud, e.g. "Ten of Hearts"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {}", self.0.rank.name(), self.0.suit.name())
     }
 }
 
 impl Card {
+    /// get a displayable long form of this card's name
+    ///
+    /// ```
+    /// # use cribbage::card::Card;
+    /// # use std::str::FromStr;
+    /// let c = Card::from_str("0H").unwrap();
+    /// assert_eq!(c.long_name().to_string(), "Ten of Hearts");
+    /// ```
     #[must_use]
-    pub fn mask(self) -> usize {
+    pub const fn long_name(self) -> LongName {
+        LongName(self)
+    }
+
+    /// the rank of this card as a usize, see [`mask`]
+    #[must_use]
+    pub const fn mask(self) -> usize {
         mask(self.rank)
     }
 
+    /// the value of this card when counting fifteens, see [`value`]
     #[must_use]
-    pub fn value(self) -> i32 {
+    pub const fn value(self) -> i32 {
         value(self.rank)
     }
+
+    /// the canonical position of this card in the deck, from 0 to 51
+    ///
+    /// The cards are ordered by rank first (Ace to King) and then by
+    /// suit within a rank (Heart, Spade, Diamond, Club) so the index
+    /// is `4*mask + suit`. The Ace of Hearts is 0, the Ace of Spades is 1,
+    /// the Two of Hearts is 4, and the King of Clubs is 51.
+    ///
+    /// This ordering will not change so it can be used for lookup
+    /// tables and stored on disk.
+    #[must_use]
+    pub const fn index(self) -> usize {
+        4 * mask(self.rank) + self.suit as usize
+    }
+
+    /// the card at the input position in the deck, see [`Card::index`]
+    ///
+    /// # Panics
+    /// - if the index is not less than 52
+    #[must_use]
+    pub const fn from_index(index : usize) -> Self {
+        ALL_CARDS[index]
+    }
+}
+
+/// implement serde's traits through the short string form
+/// written by `Display` and read by `FromStr`
+#[cfg(feature = "serde")]
+macro_rules! serde_via_str {
+    ($($t:ty),*) => {$(
+        impl serde::Serialize for $t {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $t {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                Self::from_str(&s).map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
+}
+
+// a card is stored as its two-character string (e.g. "0H") and
+// the rank and suit are stored as their single character
+#[cfg(feature = "serde")]
+serde_via_str!(Suit, Rank, Card);
+
+/// a list of cards parsed from a single string
+///
+/// The cards can be separated by whitespace or commas and cards
+/// in the short form can also be written with no separation at
+/// all, so "5H 5C 0C JH", "5H,5C,0C,JH", and "5H5C0CJH" are all
+/// the same list. Long names can be used as well, e.g.
+/// "Ten of Hearts, Ace of Spades".
+///
+/// Any error is reported along with the character position of the
+/// card that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CardList(pub Vec<Card>);
+
+impl CardList {
+    /// parse the cards in `s` which starts at character `offset`
+    /// of a larger string so that errors report the position
+    /// within that larger string
+    pub(crate) fn parse_at(s: &str, offset: usize) -> Result<Self, CardParseError> {
+        let words = words(s);
+        let mut cards = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            let (pos, word) = words[i];
+            match words.get(i+1) {
+                Some((_, of)) if of.eq_ignore_ascii_case("of") => {
+                    // long form, we need to include the suit name as well
+                    let suit = words.get(i+2).map_or("", |(_, w)| w);
+                    let c = Card::from_str(&format!("{} of {}", word, suit))
+                        .map_err(|e| e.at(offset + pos))?;
+                    cards.push(c);
+                    i += 3;
+                },
+                _ => {
+                    cards.extend(concatenated(word, offset + pos)?);
+                    i += 1;
+                }
+            }
+        }
+        Ok(Self(cards))
+    }
+}
+
+/// split a string into words separated by whitespace or commas,
+/// keeping the character position each word starts at
+fn words(s: &str) -> Vec<(usize, &str)> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (pos, (byte, c)) in s.char_indices().enumerate() {
+        let separator = c.is_whitespace() || c == ',';
+        match start {
+            None if !separator => start = Some((pos, byte)),
+            Some((p, b)) if separator => {
+                out.push((p, &s[b..byte]));
+                start = None;
+            },
+            _ => {}
+        }
+    }
+    if let Some((p, b)) = start {
+        out.push((p, &s[b..]));
+    }
+    out
+}
+
+/// parse short-form cards written one after another with no separation
+///
+/// `position` is the character position of the start of `word` so that
+/// errors can report where the bad card starts
+fn concatenated(word: &str, position: usize) -> Result<Vec<Card>, CardParseError> {
+    let chars : Vec<char> = word.chars().collect();
+    let mut cards = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        // a Ten can be written with two characters
+        let len = if chars[i..].starts_with(&['1', '0']) { 3 } else { 2 };
+        let end = (i + len).min(chars.len());
+        let card : String = chars[i..end].iter().collect();
+        cards.push(Card::from_str(&card).map_err(|e| e.at(position + i))?);
+        i = end;
+    }
+    Ok(cards)
+}
+
+impl FromStr for CardList {
+    type Err = CardParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_at(s, 0)
+    }
+}
+
+impl fmt::Display for CardList {
+    /// print the cards separated by spaces
+    ///
+    /// the alternate form (`{:#}`) is passed to each card
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            if f.alternate() {
+                write!(f, "{:#}", c)?;
+            } else {
+                write!(f, "{}", c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// parse a string into a `Vec<Card>`
+///
+/// This is a short-hand for parsing a [`CardList`] which is helpful
+/// when writing out known cards in tests and examples.
+///
+/// ```
+/// # use cribbage::cards;
+/// let cards = cards!("5H 5C 0C JH");
+/// assert_eq!(cards.len(), 4);
+/// assert_eq!(cards, cards!("5H5C10CJH"));
+/// ```
+///
+/// # Panics
+/// - if the string cannot be parsed into a list of cards
+#[macro_export]
+macro_rules! cards {
+    ($s:expr) => {
+        <$crate::card::CardList as ::std::str::FromStr>::from_str($s)
+            .unwrap_or_else(|e| panic!("{:?} is not a list of cards: {}", $s, e))
+            .0
+    };
 }
 
 #[cfg(test)]
@@ -229,6 +716,150 @@ mod tests {
         Card::from_str("A").unwrap();
     }
 
+    #[test]
+    fn card_from_str_errors() {
+        assert_eq!(Card::from_str("AHX"), Err(CardParseError::BadLength(3)));
+        assert_eq!(Card::from_str("XH"), Err(CardParseError::UnknownRank('X')));
+        assert_eq!(Card::from_str("AX"), Err(CardParseError::UnknownSuit('X')));
+        assert_eq!(Card::from_str("Aé"), Err(CardParseError::NonAscii('é')));
+        assert_eq!(Card::from_str("éA"), Err(CardParseError::NonAscii('é')));
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let e = Card::from_str("1H").unwrap_err().at(3);
+        assert_eq!(e.to_string(), "card at position 3: unknown rank '1'");
+        assert!(std::error::Error::source(&e).is_some());
+    }
+
+    #[test]
+    fn card_from_str_flexible() {
+        let ten_hearts = Card { suit : Suit::Heart, rank : Rank::Ten };
+        for s in ["TH", "0H", "10H", "th", "10h", "10♥", "T♡", "Ten of Hearts", "ten of hearts", "10 of ♥", " TH "] {
+            assert_eq!(Card::from_str(s), Ok(ten_hearts), "{}", s);
+        }
+        let ace_spades = Card { suit : Suit::Spade, rank : Rank::Ace };
+        for s in ["AS", "as", "A♠", "ace of spades", "Ace Of Spade"] {
+            assert_eq!(Card::from_str(s), Ok(ace_spades), "{}", s);
+        }
+    }
+
+    #[test]
+    fn card_from_str_flexible_errors() {
+        assert_eq!(Card::from_str("11H"), Err(CardParseError::BadLength(3)));
+        assert_eq!(Card::from_str("Eleven of Hearts"), Err(CardParseError::UnknownWord("Eleven".to_string())));
+        assert_eq!(Card::from_str("Ten of Cups"), Err(CardParseError::UnknownWord("Cups".to_string())));
+        assert_eq!(Card::from_str("Ten Hearts"), Err(CardParseError::UnknownWord("Ten Hearts".to_string())));
+        assert_eq!(Card::from_str("A★"), Err(CardParseError::NonAscii('★')));
+    }
+
+    #[test]
+    fn card_display_forms() {
+        let c = Card { suit : Suit::Diamond, rank : Rank::Ten };
+        assert_eq!(format!("{}", c), "0D");
+        assert_eq!(format!("{:#}", c), "10♦");
+        assert_eq!(c.long_name().to_string(), "Ten of Diamonds");
+        let c = Card { suit : Suit::Club, rank : Rank::Queen };
+        assert_eq!(format!("{:#}", c), "Q♣");
+        assert_eq!(Card::from_str(&c.long_name().to_string()), Ok(c));
+    }
+
+    #[test]
+    fn card_list_forms() {
+        let expected = vec![
+            Card { suit : Suit::Heart, rank : Rank::Five },
+            Card { suit : Suit::Club, rank : Rank::Five },
+            Card { suit : Suit::Club, rank : Rank::Ten },
+            Card { suit : Suit::Heart, rank : Rank::Jack },
+        ];
+        for s in ["5H 5C 0C JH", "5H,5C,0C,JH", "5H, 5C, 10C, JH", "5H5C0CJH", "5h5c10cjh", "5H5C 10C,JH",
+                  "five of hearts, 5C, Ten of Clubs Jack of Hearts"] {
+            assert_eq!(CardList::from_str(s), Ok(CardList(expected.clone())), "{}", s);
+        }
+        assert_eq!(CardList::from_str(" "), Ok(CardList(vec![])));
+        assert_eq!(CardList(expected).to_string(), "5H 5C 0C JH");
+    }
+
+    #[test]
+    fn card_list_errors() {
+        assert_eq!(CardList::from_str("5H 5X"), Err(CardParseError::UnknownSuit('X').at(3)));
+        assert_eq!(CardList::from_str("5H5C0"), Err(CardParseError::BadLength(1).at(4)));
+        assert_eq!(CardList::from_str("5H, Ten of Cups"), Err(CardParseError::UnknownWord("Cups".to_string()).at(4)));
+    }
+
+    #[test]
+    fn cards_macro() {
+        assert_eq!(cards!("AH 2h"), vec![
+            Card { suit : Suit::Heart, rank : Rank::Ace },
+            Card { suit : Suit::Heart, rank : Rank::Two },
+        ]);
+    }
+
+    #[test]
+    fn index_round_trip() {
+        for (i, c) in ALL_CARDS.iter().enumerate() {
+            assert_eq!(c.index(), i);
+            assert_eq!(Card::from_index(i), *c);
+        }
+        assert_eq!(Card::from_str("AH").unwrap().index(), 0);
+        assert_eq!(Card::from_str("AS").unwrap().index(), 1);
+        assert_eq!(Card::from_str("2H").unwrap().index(), 4);
+        assert_eq!(Card::from_str("KC").unwrap().index(), 51);
+    }
+
+    #[test]
+    fn const_tables_match_iter() {
+        use strum::IntoEnumIterator;
+        assert!(Suit::iter().eq(ALL_SUITS));
+        assert!(Rank::iter().eq(ALL_RANKS));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_card_as_string() {
+        let c = Card::from_str("0H").unwrap();
+        assert_eq!(serde_json::to_string(&c).unwrap(), "\"0H\"");
+        assert_eq!(serde_json::to_string(&c.rank).unwrap(), "\"0\"");
+        assert_eq!(serde_json::to_string(&c.suit).unwrap(), "\"H\"");
+        assert_eq!(serde_json::from_str::<Card>("\"10h\"").unwrap(), c);
+        assert!(serde_json::from_str::<Card>("\"1H\"").is_err());
+    }
+
+    #[test]
+    fn rank_next_prev() {
+        assert_eq!(Rank::Ace.prev(), None);
+        assert_eq!(Rank::Ace.next(), Some(Rank::Two));
+        assert_eq!(Rank::Ten.next(), Some(Rank::Jack));
+        assert_eq!(Rank::Jack.prev(), Some(Rank::Ten));
+        assert_eq!(Rank::King.next(), None);
+        for r in ALL_RANKS.iter().skip(1) {
+            assert_eq!(r.prev().and_then(Rank::next), Some(*r));
+        }
+    }
+
+    #[test]
+    fn rank_pip_value() {
+        assert_eq!(Rank::Ace.pip_value(), 1);
+        assert_eq!(Rank::Nine.pip_value(), 9);
+        assert_eq!(Rank::Queen.pip_value(), 10);
+    }
+
+    #[test]
+    fn card_orderings() {
+        let mut cards = cards!("KH 0C JS 2D 0H");
+        cards.sort_by_key(|c| ByRank(*c));
+        assert_eq!(cards, cards!("2D 0H 0C JS KH"));
+        cards.sort_by_key(|c| ByValue(*c));
+        assert_eq!(cards, cards!("2D 0H 0C JS KH"));
+        cards.sort_by_key(|c| BySuitThenRank(*c));
+        assert_eq!(cards, cards!("0H KH JS 2D 0C"));
+        let mut sorted = cards.clone();
+        sorted.sort();
+        assert_eq!(sorted, cards);
+        assert!(ByValue(cards!("KH")[0]) > ByValue(cards!("9C")[0]));
+        assert!(ByValue(cards!("0S")[0]) < ByValue(cards!("JH")[0]));
+    }
+
     #[test]
     fn ten_mask() {
         assert_eq!(Card::from_str("0C").unwrap().mask(), 9)
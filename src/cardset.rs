@@ -0,0 +1,474 @@
+use crate::card::{ Card, Suit, ALL_SUITS };
+use std::cmp::Reverse;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Not, Sub};
+
+/// the bits that correspond to actual cards, the top 12 bits are unused
+const FULL_MASK : u64 = (1 << 52) - 1;
+
+/// the position of a card's bit within the set
+///
+/// this is the card's index so that iterating over the bits
+/// in order goes through the deck Ace to King
+const fn bit(c : Card) -> usize {
+    c.index()
+}
+
+/// the bits of the cards in the first suit (Hearts), one for each rank
+const SUIT_MASK : u64 = 0x1_1111_1111_1111;
+
+/// the relabeling of suits that sorts the input keys from largest to smallest
+///
+/// The key for each suit is some summary of the cards in that suit and
+/// the returned array holds the new suit for each old suit. Suits with
+/// equal keys are interchangeable so the number of distinct relabelings
+/// that give a different set of cards is also returned.
+pub(crate) fn relabel_suits(keys : [u32; 4]) -> ([Suit; 4], usize) {
+    let mut order = ALL_SUITS;
+    order.sort_by_key(|s| Reverse(keys[*s as usize]));
+    let mut relabel = ALL_SUITS;
+    for (new, old) in order.iter().enumerate() {
+        relabel[*old as usize] = ALL_SUITS[new];
+    }
+
+    // the number of distinct relabelings is 4! divided by
+    //  the number of ways to shuffle each group of equal keys
+    let mut class_size = 24;
+    let mut start = 0;
+    while start < 4 {
+        let key = keys[order[start] as usize];
+        let len = order[start..].iter().take_while(|s| keys[**s as usize] == key).count();
+        class_size /= (1..=len).product::<usize>();
+        start += len;
+    }
+    (relabel, class_size)
+}
+
+/// a set of cards from a single 52-card deck
+///
+/// each card has a single bit in a `u64` so that the set operations
+/// (union, difference, containment) are just bitwise operations and
+/// a set can be copied around freely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    /// the set containing no cards
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// the set containing all 52 cards
+    #[must_use]
+    pub const fn full() -> Self {
+        Self(FULL_MASK)
+    }
+
+    /// construct a set directly from its bits
+    ///
+    /// any bits above the 52 used for cards are dropped
+    #[must_use]
+    pub const fn from_bits(bits : u64) -> Self {
+        Self(bits & FULL_MASK)
+    }
+
+    /// the underlying bits of this set
+    #[must_use]
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// the number of cards in the set
+    #[must_use]
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// check if there are no cards in the set
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// check if the input card is in this set
+    #[must_use]
+    pub fn contains(self, c : Card) -> bool {
+        self.0 & (1 << bit(c)) != 0
+    }
+
+    /// put a card into the set, returning `true` if it wasn't already there
+    pub fn insert(&mut self, c : Card) -> bool {
+        let had = self.contains(c);
+        self.0 |= 1 << bit(c);
+        !had
+    }
+
+    /// take a card out of the set, returning `true` if it was there
+    pub fn remove(&mut self, c : Card) -> bool {
+        let had = self.contains(c);
+        self.0 &= !(1 << bit(c));
+        had
+    }
+
+    /// all cards that are in either set
+    #[must_use]
+    pub const fn union(self, other : Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// the cards that are in both sets
+    #[must_use]
+    pub const fn intersection(self, other : Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// the cards in this set that are not in the other set
+    #[must_use]
+    pub const fn difference(self, other : Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// all of the cards in the deck that are not in this set
+    #[must_use]
+    pub const fn complement(self) -> Self {
+        Self(!self.0 & FULL_MASK)
+    }
+
+    /// check if all the cards in this set are also in the other set
+    #[must_use]
+    pub const fn is_subset(self, other : Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// iterate over the cards in this set
+    ///
+    /// the cards are visited in rank order (Ace to King) and
+    /// then suit order within each rank
+    #[must_use]
+    pub const fn iter(self) -> Iter {
+        Iter(self.0)
+    }
+
+    /// the ranks held in the input suit as a 13-bit mask
+    ///
+    /// bit `i` is set if the card with rank mask `i` (see [`crate::card::mask`])
+    /// and the input suit is in this set
+    #[must_use]
+    pub const fn suit_ranks(self, suit : Suit) -> u16 {
+        let mut ranks = 0;
+        let mut r = 0;
+        while r < 13 {
+            if self.0 & (1 << (4 * r + suit as usize)) != 0 {
+                ranks |= 1 << r;
+            }
+            r += 1;
+        }
+        ranks
+    }
+
+    /// construct a set from the ranks held in each suit
+    ///
+    /// this is the inverse of [`CardSet::suit_ranks`]
+    #[must_use]
+    pub const fn from_suit_ranks(ranks : [u16; 4]) -> Self {
+        let mut bits = 0;
+        let mut r = 0;
+        while r < 13 {
+            let mut s = 0;
+            while s < 4 {
+                if ranks[s] & (1 << r) != 0 {
+                    bits |= 1 << (4 * r + s);
+                }
+                s += 1;
+            }
+            r += 1;
+        }
+        Self(bits)
+    }
+
+    /// the ranks held in each suit, see [`CardSet::suit_ranks`]
+    fn all_suit_ranks(self) -> [u16; 4] {
+        ALL_SUITS.map(|s| self.suit_ranks(s))
+    }
+
+    /// move the cards of each suit to a new suit
+    ///
+    /// `relabel[s]` is the new suit for cards that currently have suit `s`
+    #[must_use]
+    pub fn relabel(self, relabel : [Suit; 4]) -> Self {
+        let mut bits = 0;
+        for s in ALL_SUITS {
+            let shift = relabel[s as usize] as i32 - s as i32;
+            let in_suit = self.0 & SUIT_MASK << s as usize;
+            bits |= if shift >= 0 { in_suit << shift } else { in_suit >> -shift };
+        }
+        Self(bits)
+    }
+
+    /// the representative of this set when the suits are relabeled
+    ///
+    /// Scoring only cares whether cards share a suit and not which suit
+    /// it is, so sets that are the same up to swapping suits around are
+    /// equivalent. The representative gives the suits with "larger" sets
+    /// of ranks the earlier suits (Hearts, then Spades, Diamonds, Clubs).
+    #[must_use]
+    pub fn canonical(self) -> Self {
+        let (relabel, _) = relabel_suits(self.all_suit_ranks().map(u32::from));
+        self.relabel(relabel)
+    }
+
+    /// the number of distinct sets that share this set's canonical form
+    ///
+    /// this is at most 24 (the number of ways to relabel the suits)
+    #[must_use]
+    pub fn class_size(self) -> usize {
+        relabel_suits(self.all_suit_ranks().map(u32::from)).1
+    }
+
+    /// iterate over all of the subsets of this set that have `k` cards
+    ///
+    /// this does not allocate for each subset so it is a cheap way to
+    /// enumerate draws from the remaining cards in a deck
+    #[must_use]
+    pub fn combinations(self, k : usize) -> Combinations {
+        Combinations::new(self, k)
+    }
+}
+
+/// an iterator over the cards in a [`CardSet`]
+#[derive(Debug, Clone)]
+pub struct Iter(u64);
+
+impl Iterator for Iter {
+    type Item = Card;
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let b = self.0.trailing_zeros() as usize;
+        // clear the lowest set bit
+        self.0 &= self.0 - 1;
+        Some(Card::from_index(b))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.0.count_ones() as usize;
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = Iter;
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+/// an iterator over the subsets of a [`CardSet`] of a fixed size
+///
+/// constructed by [`CardSet::combinations`]
+#[derive(Debug, Clone)]
+pub struct Combinations {
+    /// the bit for each card in the set we are choosing from
+    bits : Vec<u64>,
+    /// the indices into `bits` of the current subset, `None` when done
+    indices : Option<Vec<usize>>,
+}
+
+impl Combinations {
+    fn new(set : CardSet, k : usize) -> Self {
+        let bits : Vec<u64> = set.iter().map(|c| 1 << bit(c)).collect();
+        let indices = if k <= bits.len() { Some((0..k).collect()) } else { None };
+        Self { bits, indices }
+    }
+}
+
+impl Iterator for Combinations {
+    type Item = CardSet;
+    fn next(&mut self) -> Option<CardSet> {
+        let indices = self.indices.as_mut()?;
+        let current = CardSet(indices.iter().map(|&i| self.bits[i]).sum());
+
+        // advance to the next subset in lexicographic order of the indices,
+        //  find the right-most index that can still be incremented and then
+        //  reset all of the ones after it to follow directly
+        let n = self.bits.len();
+        let k = indices.len();
+        match (0..k).rev().find(|&i| indices[i] != i + n - k) {
+            Some(i) => {
+                indices[i] += 1;
+                for j in i+1..k {
+                    indices[j] = indices[j-1] + 1;
+                }
+            },
+            None => self.indices = None
+        }
+        Some(current)
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter : I) -> Self {
+        let mut s = Self::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl<'a> FromIterator<&'a Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = &'a Card>>(iter : I) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, iter : I) {
+        for c in iter {
+            self.insert(c);
+        }
+    }
+}
+
+impl From<Card> for CardSet {
+    fn from(c : Card) -> Self {
+        Self(1 << bit(c))
+    }
+}
+
+impl From<&[Card]> for CardSet {
+    fn from(cards : &[Card]) -> Self {
+        cards.iter().collect()
+    }
+}
+
+impl From<CardSet> for Vec<Card> {
+    fn from(s : CardSet) -> Self {
+        s.iter().collect()
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = Self;
+    fn bitor(self, rhs : Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = Self;
+    fn bitand(self, rhs : Self) -> Self {
+        self.intersection(rhs)
+    }
+}
+
+impl Sub for CardSet {
+    type Output = Self;
+    fn sub(self, rhs : Self) -> Self {
+        self.difference(rhs)
+    }
+}
+
+impl Not for CardSet {
+    type Output = Self;
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+impl fmt::Display for CardSet {
+    /// print the cards in the set separated by spaces
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards;
+
+    #[test]
+    fn full_set() {
+        let full = CardSet::full();
+        assert_eq!(full.len(), 52);
+        assert!(!full.is_empty());
+        assert!(CardSet::new().is_empty());
+        assert_eq!(full.complement(), CardSet::new());
+    }
+
+    #[test]
+    fn rank_suit_order() {
+        let s : CardSet = cards!("KC 2H AS AH").into_iter().collect();
+        assert_eq!(s.iter().collect::<Vec<Card>>(), cards!("AH AS 2H KC"));
+        assert_eq!(s.to_string(), "AH AS 2H KC");
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a = CardSet::from(&cards!("AH 2H 3H")[..]);
+        let b = CardSet::from(&cards!("3H 4H")[..]);
+        assert_eq!((a | b).len(), 4);
+        assert_eq!(a & b, CardSet::from(cards!("3H")[0]));
+        assert_eq!(Vec::from(a - b), cards!("AH 2H"));
+        assert!((a - b).is_subset(a));
+        assert!(!a.is_subset(b));
+        assert!(!(!a).contains(cards!("2H")[0]));
+    }
+
+    #[test]
+    fn insert_remove() {
+        let c = cards!("5D")[0];
+        let mut s = CardSet::new();
+        assert!(s.insert(c));
+        assert!(!s.insert(c));
+        assert!(s.contains(c));
+        assert!(s.remove(c));
+        assert!(!s.remove(c));
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn suit_ranks_round_trip() {
+        let s = CardSet::from(&cards!("AH 3H KH 2S 0C")[..]);
+        assert_eq!(s.suit_ranks(Suit::Heart), 0b1_0000_0000_0101);
+        assert_eq!(s.suit_ranks(Suit::Diamond), 0);
+        assert_eq!(CardSet::from_suit_ranks(s.all_suit_ranks()), s);
+    }
+
+    #[test]
+    fn canonical_under_relabeling() {
+        use itertools::Itertools;
+        let s = CardSet::from(&cards!("AC 3C 5D 5S 0S JH")[..]);
+        let canon = s.canonical();
+        assert_eq!(canon, CardSet::from(&cards!("JH 5S 0S 5D AC 3C")[..]));
+        let mut images = std::collections::HashSet::new();
+        for perm in ALL_SUITS.iter().copied().permutations(4) {
+            let image = s.relabel(perm.try_into().unwrap());
+            assert_eq!(image.len(), s.len());
+            assert_eq!(image.canonical(), canon);
+            images.insert(image);
+        }
+        assert_eq!(images.len(), s.class_size());
+        assert_eq!(CardSet::from(&cards!("AH AS AD AC")[..]).class_size(), 1);
+    }
+
+    #[test]
+    fn combination_counts() {
+        assert_eq!(CardSet::full().combinations(2).count(), 1326);
+        let few = CardSet::from(&cards!("AH 2H 3H 4H 5H")[..]);
+        assert_eq!(few.combinations(3).count(), 10);
+        assert_eq!(few.combinations(0).count(), 1);
+        assert_eq!(few.combinations(6).count(), 0);
+        assert!(few.combinations(3).all(|s| s.len() == 3 && s.is_subset(few)));
+    }
+}
@@ -0,0 +1,363 @@
+use crate::card::{ mask, Card, Suit, ALL_RANKS };
+use crate::cardset::CardSet;
+use crate::deck;
+use crate::lut::{ check_file, LutError };
+use crate::score::{ score_fast, HandKind, ScoringRules };
+use crate::strategy::{ crib_total, discard_options, hand_total, tie_break, Context, Strategy };
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::fs;
+use std::io::{ self, Write };
+use std::path::Path;
+
+/// the number of different two card discards up to relabeling the suits
+///
+/// the discards are laid out on a 13 by 13 grid of ranks with the
+/// suited discards above the diagonal and the rest on or below it
+pub const CLASSES : usize = 13 * 13;
+
+/// the bytes at the start of every crib table file
+pub const MAGIC : [u8; 8] = *b"CRIBDISC";
+
+/// the version of the file format written by [`CribTable::to_bytes`]
+pub const VERSION : u16 = 1;
+
+/// the number of bytes before the values start
+///
+/// the header is laid out as
+/// - `0..8` [`MAGIC`]
+/// - `8..10` [`VERSION`] as a little-endian integer
+/// - `10..12` unused, always 0
+/// - `12..16` the number of deals sampled for the optimal opponent
+///
+/// and is followed by the three tables (see [`CribTable::values`])
+/// one after the other, each value a little-endian `f32`
+pub const HEADER_LEN : usize = 16;
+
+/// the number of bytes in a crib table file
+pub const FILE_LEN : usize = HEADER_LEN + 3 * CLASSES * 4;
+
+/// how the opponent is assumed to pick their discard into the crib
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opponent {
+    /// the opponent discards two cards at random
+    Random,
+    /// the opponent keeps the hand with the best average score
+    /// plus or minus what their discard is worth to the crib
+    /// (using the [`Opponent::Random`] values)
+    Optimal,
+}
+
+/// where a two card discard sits in a table
+///
+/// only the ranks and whether the cards share a suit matter
+/// since the rest of the crib and the cut are unknown
+#[must_use]
+pub fn class_index(a : Card, b : Card) -> usize {
+    let (lo, hi) = if mask(a.rank) <= mask(b.rank) { (mask(a.rank), mask(b.rank)) } else { (mask(b.rank), mask(a.rank)) };
+    if a.suit == b.suit && lo != hi {
+        lo * 13 + hi
+    } else {
+        hi * 13 + lo
+    }
+}
+
+/// a discard that sits at the input place in a table
+fn representative(class : usize) -> [Card; 2] {
+    let (row, col) = (class / 13, class % 13);
+    let second = if row < col { Suit::Heart } else { Suit::Spade };
+    [Card { suit : Suit::Heart, rank : ALL_RANKS[row] }, Card { suit : second, rank : ALL_RANKS[col] }]
+}
+
+/// the average points each two card discard is worth in the crib
+///
+/// There is a value for the dealer and the pone under each assumption
+/// about how the opponent discards, though against a random opponent
+/// they are the same and only stored once. The values are always the
+/// points in the crib, it's up to the user to subtract them as the pone.
+///
+/// ```no_run
+/// # use cribbage::crib_table::{CribTable, Opponent};
+/// # use cribbage::cards;
+/// let table = CribTable::load("crib.table").unwrap();
+/// let fives = table.value(&cards!("5H 5S"), true, Opponent::Random);
+/// assert!(fives > table.value(&cards!("KH 9S"), true, Opponent::Random));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CribTable {
+    values : [[f32; CLASSES]; 3],
+    samples : u32,
+}
+
+impl CribTable {
+    /// work out the value of every discard
+    ///
+    /// The values against a random opponent are exact, going through every
+    /// pair of cards the opponent could discard and every cut. The values
+    /// against an optimal opponent deal the opponent `samples` random hands
+    /// for each discard and average over every cut after they choose
+    /// their discard. The hands are dealt by a ChaCha8 generator seeded
+    /// by `seed`, which unlike [`rand::rngs::StdRng`] is fixed from one
+    /// release of `rand` to the next, so the same seed gives the same table.
+    /// With no samples there are no values against an optimal opponent
+    /// and looking one up panics (see [`CribTable::value`]).
+    #[must_use]
+    pub fn generate(rules : &ScoringRules, samples : u32, seed : u64) -> Self {
+        let mut random = [0.0; CLASSES];
+        for (class, v) in random.iter_mut().enumerate() {
+            let (total, n) = crib_total(&representative(class), CardSet::new(), rules);
+            *v = (total as f64 / n as f64) as f32;
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut optimal = [[0.0; CLASSES]; 2];
+        if samples > 0 {
+            let mut opponent = TableCribValue::new(
+                Self { values : [random, [0.0; CLASSES], [0.0; CLASSES]], samples : 0 },
+                Opponent::Random,
+                *rules
+            );
+            for (table, dealer) in optimal.iter_mut().zip([true, false]) {
+                for (class, v) in table.iter_mut().enumerate() {
+                    *v = sample_optimal(&representative(class), dealer, &mut opponent, samples, &mut rng, rules);
+                }
+            }
+        }
+        Self { values : [random, optimal[0], optimal[1]], samples }
+    }
+
+    /// the three tables of values, each indexed by [`class_index`]
+    ///
+    /// in order they are for either player against a random opponent
+    /// (a random discard doesn't depend on who deals), the dealer against
+    /// an optimal opponent and the pone against an optimal opponent
+    #[must_use]
+    pub const fn values(&self) -> &[[f32; CLASSES]; 3] {
+        &self.values
+    }
+
+    /// the number of deals sampled for each value against an optimal opponent
+    #[must_use]
+    pub const fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// the average points the discard is worth in the crib
+    ///
+    /// This is an approximation since the table only knows the two cards
+    /// discarded. The rest of the crib and the cut are drawn as if every
+    /// other card were still in the deck, including the four cards the
+    /// player kept, so it can differ a little from
+    /// [`crate::strategy::expected_crib_value_with`] given the whole deal.
+    ///
+    /// # Panics
+    /// - if the discard isn't two cards
+    /// - if asking about an optimal opponent when no deals were sampled
+    #[must_use]
+    pub fn value(&self, discard : &[Card], dealer : bool, opponent : Opponent) -> f64 {
+        assert!(discard.len() == 2, "the crib table only holds two card discards");
+        assert!(opponent == Opponent::Random || self.samples > 0, "the crib table has no values against an optimal opponent");
+        let table = match (opponent, dealer) {
+            (Opponent::Random, _) => 0,
+            (Opponent::Optimal, true) => 1,
+            (Opponent::Optimal, false) => 2,
+        };
+        self.values[table][class_index(discard[0], discard[1])].into()
+    }
+
+    /// the table in the form it is stored on disk
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(FILE_LEN);
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&[0, 0]);
+        out.extend_from_slice(&self.samples.to_le_bytes());
+        for v in self.values.iter().flatten() {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        out
+    }
+
+    /// read a table in the form written by [`CribTable::to_bytes`]
+    ///
+    /// # Errors
+    /// - if the bytes aren't a crib table this version can read
+    pub fn from_bytes(bytes : &[u8]) -> Result<Self, LutError> {
+        check_file(bytes, &MAGIC, VERSION, FILE_LEN)?;
+        let samples = u32::from_le_bytes(bytes[12..16].try_into().unwrap());
+        let mut values = [[0.0; CLASSES]; 3];
+        let floats = bytes[HEADER_LEN..].chunks_exact(4).map(|b| f32::from_le_bytes(b.try_into().unwrap()));
+        for (v, f) in values.iter_mut().flatten().zip(floats) {
+            *v = f;
+        }
+        Ok(Self { values, samples })
+    }
+
+    /// write the table to a file
+    ///
+    /// # Errors
+    /// - if writing the file fails
+    pub fn write<W : Write>(&self, mut w : W) -> io::Result<()> {
+        w.write_all(&self.to_bytes())?;
+        w.flush()
+    }
+
+    /// read a table from a file written by [`CribTable::write`]
+    ///
+    /// # Errors
+    /// - if the file can't be read or isn't a crib table
+    pub fn load<P : AsRef<Path>>(path : P) -> Result<Self, LutError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+/// the average points of our discard in the crib when the opponent
+/// picks their discard from random deals of the cards left
+fn sample_optimal(
+    discard : &[Card; 2],
+    dealer : bool,
+    opponent : &mut TableCribValue,
+    samples : u32,
+    rng : &mut ChaCha8Rng,
+    rules : &ScoringRules
+) -> f32 {
+    // the opponent is the dealer exactly when we aren't
+    let context = Context { dealer : !dealer, ..Context::default() };
+    let mut left : Vec<Card> = deck::remaining(CardSet::from(&discard[..])).iter().collect();
+    let mut total = 0;
+    let mut n = 0;
+    for _ in 0..samples {
+        let (dealt, _) = left.partial_shuffle(rng, 6);
+        let theirs = opponent.choose(dealt, &context);
+        let crib = [discard[0], discard[1], theirs[0], theirs[1]];
+        for cut in deck::remaining(CardSet::from(&crib[..]) | CardSet::from(&dealt[..])) {
+            total += score_fast(&crib, cut, rules, HandKind::Crib);
+            n += 1;
+        }
+    }
+    (total as f64 / n as f64) as f32
+}
+
+/// keep the four cards with the best average hand score plus (as the
+/// dealer) or minus (as the pone) the value of the discard from a table
+///
+/// This makes the same kind of choice as
+/// [`crate::strategy::ExpectedHandAndCribValue`] but looks the crib up
+/// instead of working it out every time, so it is quick enough for
+/// simulations. The catch is that the table doesn't know which cards
+/// were kept (see [`CribTable::value`]) and still counts them as cards
+/// that could end up in the crib or be cut, so the two strategies can
+/// pick different discards when the crib values are close.
+#[derive(Debug, Clone)]
+pub struct TableCribValue {
+    table : CribTable,
+    opponent : Opponent,
+    rules : ScoringRules,
+}
+
+impl TableCribValue {
+    /// a strategy using the input table assuming the opponent discards
+    /// like the input model
+    ///
+    /// # Panics
+    /// - if the opponent is optimal and the table has no samples
+    #[must_use]
+    pub fn new(table : CribTable, opponent : Opponent, rules : ScoringRules) -> Self {
+        assert!(opponent == Opponent::Random || table.samples() > 0, "the crib table has no values against an optimal opponent");
+        Self { table, opponent, rules }
+    }
+}
+
+impl Strategy for TableCribValue {
+    /// # Panics
+    /// - if the number of cards dealt isn't six
+    fn choose(&mut self, cards_dealt : &[Card], context : &Context<'_>) -> Vec<Card> {
+        assert!(cards_dealt.len() == 6, "the crib table only holds two card discards");
+        let seen = CardSet::from(cards_dealt) | CardSet::from(context.known_cards);
+        let cuts = deck::remaining(seen).len() as f64;
+        discard_options(cards_dealt)
+            .into_iter()
+            .map(|(kept, crib)| {
+                let hand = hand_total(&kept, seen, &self.rules) as f64 / cuts;
+                let crib_value = self.table.value(&crib, context.dealer, self.opponent);
+                let value = if context.dealer { hand + crib_value } else { hand - crib_value };
+                (value, crib)
+            })
+            .max_by(|(a, ca), (b, cb)| a.total_cmp(b).then_with(|| tie_break(ca).cmp(&tie_break(cb))))
+            .map(|(_, crib)| crib)
+            .expect("six cards were dealt")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards;
+    use crate::strategy::expected_crib_value;
+    use std::sync::OnceLock;
+
+    /// generating a table takes a while so the tests share one
+    fn table() -> &'static CribTable {
+        static TABLE : OnceLock<CribTable> = OnceLock::new();
+        TABLE.get_or_init(|| CribTable::generate(&ScoringRules::default(), 2, 3))
+    }
+
+    #[test]
+    fn classes_cover_every_discard() {
+        let mut seen = [0; CLASSES];
+        for pair in CardSet::full().combinations(2) {
+            let cs : Vec<Card> = pair.iter().collect();
+            seen[class_index(cs[0], cs[1])] += 1;
+            assert_eq!(class_index(cs[0], cs[1]), class_index(cs[1], cs[0]));
+        }
+        for (class, n) in seen.iter().enumerate() {
+            let [a, b] = representative(class);
+            assert_eq!(class_index(a, b), class);
+            // pairs and offsuit cards have 6 and 12 ways to pick suits, suited cards 4
+            assert_eq!(*n, if a.rank == b.rank { 6 } else if a.suit == b.suit { 4 } else { 12 });
+        }
+    }
+
+    #[test]
+    fn random_matches_expected_crib_value() {
+        let table = table();
+        for d in [cards!("5H 5S"), cards!("7D 8D"), cards!("KC 2H")] {
+            let v = table.value(&d, true, Opponent::Random);
+            assert!((v - expected_crib_value(&d, true)).abs() < 1e-4);
+            assert_eq!(v, table.value(&d, false, Opponent::Random));
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let bytes = table().to_bytes();
+        assert_eq!(bytes.len(), FILE_LEN);
+        assert_eq!(&CribTable::from_bytes(&bytes).unwrap(), table());
+        assert!(matches!(CribTable::from_bytes(&bytes[1..]), Err(LutError::WrongLength { .. })));
+    }
+
+    #[test]
+    fn sampling_is_reproducible() {
+        let a = CribTable::generate(&ScoringRules::default(), 2, 3);
+        assert_eq!(&a, table());
+        assert!(a.value(&cards!("5H 5S"), true, Opponent::Optimal) > 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "no values against an optimal opponent")]
+    fn optimal_needs_samples() {
+        let table = CribTable { values : [[1.0; CLASSES]; 3], samples : 0 };
+        assert_eq!(table.value(&cards!("5H 5S"), true, Opponent::Random), 1.0);
+        let _ = table.value(&cards!("5H 5S"), true, Opponent::Optimal);
+    }
+
+    #[test]
+    fn table_strategy() {
+        let dealt = cards!("5H 5S 6D 7C KH QS");
+        let mut s = TableCribValue::new(table().clone(), Opponent::Random, ScoringRules::default());
+        let as_pone = s.choose(&dealt, &Context::default());
+        assert_eq!(as_pone.len(), 2);
+        assert!(!as_pone.iter().any(|c| c.rank == crate::card::Rank::Five));
+    }
+}
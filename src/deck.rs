@@ -1,24 +1,18 @@
 
 use std::collections::HashMap;
-use crate::card::{
-    Rank,
-    Suit,
-    Card
-};
+use crate::card::{ Card, ALL_SUITS };
+use crate::cardset::{ self, CardSet };
 use crate::hand::Hand;
-use strum::IntoEnumIterator;
-use itertools::Itertools;
+use crate::score::{ HandKind, ScoringRules };
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 /// the full deck of cards
 ///
-/// this does some iterator magics to construct all pairs of
-/// suits and ranks and therefore all 52 cards
+/// the cards are ordered by rank and then suit within each rank
 #[must_use]
 pub fn full() -> Vec<Card> {
-    Rank::iter()
-        .cartesian_product(Suit::iter())
-        .map(|(r,s)| Card { rank : r, suit : s })
-        .collect()
+    CardSet::full().iter().collect()
 }
 
 /// a view of the deck with the input cards removed
@@ -28,23 +22,242 @@ pub fn full() -> Vec<Card> {
 /// cards given a set of cards that has already been drawn
 #[must_use]
 pub fn part(already_drawn : &[Card]) -> Vec<Card> {
-    full().iter().filter(|c| !already_drawn.contains(c)).copied().collect()
+    remaining(CardSet::from(already_drawn)).iter().collect()
+}
+
+/// the set of cards left in the deck after some have been drawn
+///
+/// this is the same as [`part`] but without leaving the
+/// [`CardSet`] so that it is cheap to enumerate draws from it
+#[must_use]
+pub const fn remaining(already_drawn : CardSet) -> CardSet {
+    already_drawn.complement()
+}
+
+/// all of the sets of `k` cards from the deck up to relabeling the suits
+///
+/// Each set is in its canonical form (see [`CardSet::canonical`]) and is
+/// paired with the number of sets that share that form, so the counts
+/// sum up to the number of ways to choose `k` cards from the deck while
+/// there are roughly 24 times fewer sets to go through.
+#[must_use]
+pub fn canonical_deals(k : usize) -> Vec<(CardSet, usize)> {
+    // all of the possible sets of ranks within a suit, grouped by
+    //  how many ranks are in the set and sorted within each group
+    let mut by_len = vec![Vec::new(); 14];
+    for ranks in 0..(1u16 << 13) {
+        by_len[ranks.count_ones() as usize].push(ranks);
+    }
+    let mut out = Vec::new();
+    canonical_deals_from(&by_len, [0; 4], 0, u16::MAX, k, &mut out);
+    out
+}
+
+/// fill in the ranks of the suits from `suit` onward
+///
+/// a set is canonical when the ranks held in each suit are ordered
+/// from largest to smallest, so each suit can hold at most `max`
+fn canonical_deals_from(
+    by_len : &[Vec<u16>],
+    mut ranks : [u16; 4],
+    suit : usize,
+    max : u16,
+    remaining : usize,
+    out : &mut Vec<(CardSet, usize)>
+) {
+    if suit == ranks.len() {
+        let set = CardSet::from_suit_ranks(ranks);
+        out.push((set, set.class_size()));
+        return;
+    }
+    // the last suit needs to hold all of the cards that are left
+    let min_len = if suit == ranks.len() - 1 { remaining } else { 0 };
+    for len in min_len..=remaining.min(13) {
+        for &r in by_len[len].iter().take_while(|&&r| r <= max) {
+            ranks[suit] = r;
+            canonical_deals_from(by_len, ranks, suit + 1, r, remaining - len, out);
+        }
+    }
+}
+
+/// all of the unique 5-card hands up to relabeling the suits
+///
+/// Each hand is in its canonical form (see [`Hand::canonical`]) and is
+/// paired with the number of hands that share that form. Since scoring
+/// does not depend on which suit is which, this is an exact replacement
+/// for going through all 12_994_800 hands that is roughly 24 times smaller.
+#[must_use]
+pub fn canonical_hands() -> Vec<(Hand, usize)> {
+    let mut out = Vec::new();
+    for (set, _) in canonical_deals(4) {
+        let cards : Vec<Card> = set.iter().collect();
+        for cut in remaining(set) {
+            // suits holding the same ranks in the hand are interchangeable,
+            //  so the cut only needs to go in the first of those suits
+            let ranks = set.suit_ranks(cut.suit);
+            if ALL_SUITS.iter().find(|s| set.suit_ranks(**s) == ranks) == Some(&cut.suit) {
+                let hand = Hand::from_cards(&cards, cut);
+                out.push((hand, hand.class_size()));
+            }
+        }
+    }
+    out
 }
 
 /// the full set of all unique 5-card hands
 ///
 /// this Look Up Table (LUT) is helpful for improving the speed
 /// of more complicated simulations comparing different
-/// strategies. It takes about 65s to construct the 12_994_800
-/// different possible unique scorable hands.
+/// strategies. It takes about 12s in a release build to construct
+/// the 12_994_800 different possible unique scorable hands.
+///
+/// The hands are scored as a player's hand under the standard rules,
+/// see [`unique_scores_with`] for the crib and house rules.
+///
+/// With the `rayon` feature, [`unique_scores_par`] builds the same
+/// table using all of the available cores.
 #[must_use]
 pub fn unique_scores() -> HashMap<Hand,usize> {
-    let mut lut = HashMap::new();
-    for cards in full().iter().copied().combinations(4) {
-        for cut in part(&cards) {
-            let hand = Hand::from_cards(&cards, cut);
-            lut.insert(hand,hand.score());
-        }
+    unique_scores_with(&ScoringRules::default(), HandKind::Hand)
+}
+
+/// the full set of all unique 5-card hands scored as the input kind
+/// of hand under the input rules
+///
+/// this lets tables for the crib and house rules be made as well
+/// as the standard one from [`unique_scores`]
+#[must_use]
+pub fn unique_scores_with(rules : &ScoringRules, kind : HandKind) -> HashMap<Hand,usize> {
+    all_hands_with(rules, kind).collect()
+}
+
+/// every 5-card hand along with its score, one at a time
+///
+/// Unlike [`unique_scores`] nothing is kept around after it is
+/// handed out, so this can be used to write out all 12_994_800
+/// hands without holding them in memory.
+///
+/// The order is always the same: the sets of four cards are gone
+/// through in lexicographic order of their [`Card::index`] and
+/// each is paired with the cuts from lowest index to highest.
+/// The hands are scored as a player's hand under the standard rules.
+///
+/// ```
+/// # use cribbage::deck;
+/// let mut hands = deck::all_hands();
+/// let (hand, score) = hands.next().unwrap();
+/// assert_eq!(hand.to_string(), "[AH AS AD AC] 2H");
+/// assert_eq!(score, 12);
+/// ```
+#[must_use]
+pub fn all_hands() -> AllHands {
+    all_hands_with(&ScoringRules::default(), HandKind::Hand)
+}
+
+/// every 5-card hand along with its score as the input kind of hand
+/// under the input rules, in the same order as [`all_hands`]
+#[must_use]
+pub fn all_hands_with(rules : &ScoringRules, kind : HandKind) -> AllHands {
+    let mut sets = CardSet::full().combinations(4);
+    let first = sets.next().unwrap_or_default();
+    AllHands {
+        sets,
+        cards : first.iter().collect(),
+        cuts : remaining(first).iter(),
+        rules : *rules,
+        kind,
+    }
+}
+
+/// an iterator over every 5-card hand and its score
+///
+/// constructed by [`all_hands`] or [`all_hands_with`]
+#[derive(Debug, Clone)]
+pub struct AllHands {
+    sets : cardset::Combinations,
+    cards : Vec<Card>,
+    cuts : cardset::Iter,
+    rules : ScoringRules,
+    kind : HandKind,
+}
+
+impl Iterator for AllHands {
+    type Item = (Hand, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let cut = match self.cuts.next() {
+            Some(cut) => cut,
+            None => {
+                // move on to the next four cards
+                let set = self.sets.next()?;
+                self.cards = set.iter().collect();
+                self.cuts = remaining(set).iter();
+                self.cuts.next()?
+            }
+        };
+        let hand = Hand::from_cards(&self.cards, cut);
+        Some((hand, hand.score_fast_with(&self.rules, self.kind)))
+    }
+}
+
+/// the number of four card sets scored by a thread before reporting progress
+#[cfg(feature = "rayon")]
+const PROGRESS_CHUNK : usize = 1024;
+
+/// the full set of all unique 5-card hands, built in parallel
+///
+/// This gives the same table as [`unique_scores`]. The 270,725 sets of
+/// four cards are split across rayon's thread pool and the hands scored
+/// by each thread are merged at the end.
+///
+/// As each thread finishes a chunk of sets, `progress` is called with
+/// the number of sets in that chunk so the calls sum up to 270,725.
+/// This lines up with [`indicatif::ProgressBar::inc`]:
+///
+/// ```no_run
+/// # use cribbage::deck;
+/// let bar = indicatif::ProgressBar::new(270_725);
+/// let lut = deck::unique_scores_par(|n| bar.inc(n));
+/// bar.finish();
+/// ```
+#[cfg(feature = "rayon")]
+#[must_use]
+pub fn unique_scores_par<F>(progress : F) -> HashMap<Hand,usize>
+where
+    F : Fn(u64) + Sync
+{
+    unique_scores_par_with(&ScoringRules::default(), HandKind::Hand, progress)
+}
+
+/// the full set of all unique 5-card hands scored as the input kind
+/// of hand under the input rules, built in parallel
+///
+/// this gives the same table as [`unique_scores_with`], reporting
+/// progress like [`unique_scores_par`]
+#[cfg(feature = "rayon")]
+#[must_use]
+pub fn unique_scores_par_with<F>(rules : &ScoringRules, kind : HandKind, progress : F) -> HashMap<Hand,usize>
+where
+    F : Fn(u64) + Sync
+{
+    let sets : Vec<CardSet> = CardSet::full().combinations(4).collect();
+    let chunks : Vec<Vec<(Hand, usize)>> = sets
+        .par_chunks(PROGRESS_CHUNK)
+        .map(|chunk| {
+            let mut scored = Vec::with_capacity(chunk.len() * 48);
+            for set in chunk {
+                let cards : Vec<Card> = set.iter().collect();
+                for cut in remaining(*set) {
+                    let hand = Hand::from_cards(&cards, cut);
+                    scored.push((hand, hand.score_fast_with(rules, kind)));
+                }
+            }
+            progress(chunk.len() as u64);
+            scored
+        })
+        .collect();
+    let mut lut = HashMap::with_capacity(sets.len() * 48);
+    for scored in chunks {
+        lut.extend(scored);
     }
     lut
 }
@@ -66,4 +279,63 @@ mod tests {
         assert_eq!(one_drawn.len(), 51);
         assert!(!one_drawn.contains(&card_drawn));
     }
+
+    #[test]
+    fn canonical_deal_counts() {
+        let deals = canonical_deals(4);
+        assert_eq!(deals.iter().map(|(_, n)| n).sum::<usize>(), 270_725);
+        assert!(deals.iter().all(|(s, _)| s.len() == 4 && s.canonical() == *s));
+        assert_eq!(canonical_deals(6).iter().map(|(_, n)| n).sum::<usize>(), 20_358_520);
+    }
+
+    #[test]
+    fn canonical_hand_counts() {
+        let hands = canonical_hands();
+        assert_eq!(hands.iter().map(|(_, n)| n).sum::<usize>(), 12_994_800);
+        assert!(hands.iter().all(|(h, _)| h.canonical() == *h));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    #[ignore = "slow, run with `cargo test --release --features rayon -- --ignored`"]
+    fn parallel_matches_serial() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        let done = AtomicU64::new(0);
+        let rules = ScoringRules::default();
+        let par = unique_scores_par_with(&rules, HandKind::Crib, |n| { done.fetch_add(n, Ordering::Relaxed); });
+        assert_eq!(done.into_inner(), 270_725);
+        assert_eq!(par, unique_scores_with(&rules, HandKind::Crib));
+    }
+
+    #[test]
+    fn all_hands_order() {
+        let first : Vec<Hand> = all_hands().take(49).map(|(h, _)| h).collect();
+        assert_eq!(first[0], Hand::from_str("AH AS AD AC | 2H").unwrap());
+        assert_eq!(first[47], Hand::from_str("AH AS AD AC | KC").unwrap());
+        assert_eq!(first[48], Hand::from_str("AH AS AD 2H | AC").unwrap());
+    }
+
+    #[test]
+    fn all_hands_with_rules() {
+        // the zero argument version is the standard rules for a player's hand
+        let rules = ScoringRules { nobs : 2, nineteen_bonus : 1, ..ScoringRules::default() };
+        let standard = all_hands().take(2_000);
+        let house = all_hands_with(&rules, HandKind::Crib).take(2_000);
+        let mut changed = 0;
+        for ((h, s), (hh, hs)) in standard.zip(house) {
+            assert_eq!(h, hh);
+            assert_eq!(s, h.score());
+            assert_eq!(hs, h.score_with(&rules, HandKind::Crib));
+            changed += usize::from(s != hs);
+        }
+        assert!(changed > 0);
+    }
+
+    #[test]
+    fn full_deck_order() {
+        let deck = full();
+        assert_eq!(deck[0], Card::from_str("AH").unwrap());
+        assert_eq!(deck[5], Card::from_str("2S").unwrap());
+        assert_eq!(deck[51], Card::from_str("KC").unwrap());
+    }
 }
@@ -1,8 +1,54 @@
-use crate::card::{ Rank, Card };
-use std::fmt;
+use crate::card::{ Rank, Card, CardList, CardParseError, ALL_SUITS };
+use crate::cardset::{ CardSet, relabel_suits };
+use crate::stats::ScoreDistribution;
+use crate::deck;
+use crate::score::{ ScoreBreakdown, HandKind, ScoringRules, score_fast, score_cards };
+use std::{error, fmt};
 use std::str::FromStr;
 use itertools::Itertools;
 
+/// the ways constructing a hand can go wrong
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandError {
+    /// one of the cards could not be parsed
+    Parse(CardParseError),
+    /// the number of cards in the hand (not including the cut) was not four
+    WrongCount(usize),
+    /// there was not exactly one cut card
+    WrongCutCount(usize),
+    /// there was no separator between the hand and the cut
+    MissingCut,
+    /// the same card showed up more than once
+    Duplicate(Card),
+}
+
+impl fmt::Display for HandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "{}", e),
+            Self::WrongCount(n) => write!(f, "a hand must have four cards but found {}", n),
+            Self::WrongCutCount(n) => write!(f, "a hand must have one cut card but found {}", n),
+            Self::MissingCut => write!(f, "no '|' separating the hand from the cut"),
+            Self::Duplicate(c) => write!(f, "{} shows up more than once", c),
+        }
+    }
+}
+
+impl error::Error for HandError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<CardParseError> for HandError {
+    fn from(e: CardParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
 /// a scorable hand of cards
 ///
 /// In cribbage, hands that can score points consist
@@ -11,33 +57,43 @@ use itertools::Itertools;
 /// chosen. This struct holds the four cards that a player
 /// holds in their hand (called `hand`) and the card that
 /// was flipped after the deal (called `cut`).
-#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+///
+/// The fields are only set by the constructors which keep the
+/// four cards in the hand sorted, so two hands holding the same
+/// cards are equal and hash the same no matter what order the
+/// cards were provided in.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "HandRecord", into = "HandRecord"))]
 pub struct Hand {
-    pub hand: [Card;4],
-    pub cut: Card,
+    hand: [Card;4],
+    cut: Card,
 }
 
 impl Hand {
     /// construct a new hand from a list of strings
     ///
     /// # Panics
-    /// - if any of the strings provided cannot be deduced into a Card
+    /// - if any of the strings provided cannot be deduced into a Card,
+    ///   the panic message holds the `CardParseError` describing why
     #[must_use]
     pub fn new(cs: &[&str], c : &str) -> Self {
-        let cards : Vec<Card> = cs.iter().map(|x| Card::from_str(x).unwrap()).collect();
-        Hand::from_cards(&cards,
-            Card::from_str(c).unwrap()
-            )
+        let parse = |x: &str| Card::from_str(x).unwrap_or_else(|e| panic!("{:?} is not a card: {}", x, e));
+        let cards : Vec<Card> = cs.iter().map(|x| parse(x)).collect();
+        Hand::from_cards(&cards, parse(c))
     }
 
     /// construct a new hand from a list of cards
     ///
+    /// the cards are not checked for duplicates, use [`Hand::try_new`]
+    /// or [`Hand::is_valid`] if the cards may not come from a single deck
+    ///
     /// # Panics
     /// - if the number of cards provided for the hand is not 4
     #[must_use]
-    pub fn from_cards(h : &Vec<Card>, cut : Card) -> Self {
+    pub fn from_cards(h : &[Card], cut : Card) -> Self {
         assert!(h.len() == 4, "`Hand` must contain four `Card`s");
-        let mut hand : [Card;4] = h.clone().try_into().unwrap();
+        let mut hand : [Card;4] = h.try_into().unwrap();
         // we need to sort the hand here so that the derived
         // equality and hashing can work as expected
         // I don't expect this to be a large performance burden
@@ -47,11 +103,96 @@ impl Hand {
         Self { hand, cut }
     }
 
+    /// construct a new hand from a list of cards, checking that it is possible
+    ///
+    /// unlike [`Hand::from_cards`] this does not panic, instead reporting
+    /// if the wrong number of cards was provided or if any card
+    /// (including the cut) shows up more than once
+    pub fn try_new(h : &[Card], cut : Card) -> Result<Self, HandError> {
+        if h.len() != 4 {
+            return Err(HandError::WrongCount(h.len()));
+        }
+        let hand = Self::from_cards(h, cut);
+        match hand.duplicate() {
+            Some(c) => Err(HandError::Duplicate(c)),
+            None => Ok(hand)
+        }
+    }
+
+    /// the four cards held in the hand, sorted
+    #[must_use]
+    pub const fn hand(&self) -> &[Card;4] {
+        &self.hand
+    }
+
+    /// the card that was cut after the deal
+    #[must_use]
+    pub const fn cut(&self) -> Card {
+        self.cut
+    }
+
+    /// all five cards, the hand followed by the cut
+    #[must_use]
+    pub const fn cards(&self) -> [Card;5] {
+        [self.hand[0], self.hand[1], self.hand[2], self.hand[3], self.cut]
+    }
+
+    /// check that this hand could be dealt from a single deck
+    ///
+    /// a hand is invalid if any card shows up more than once,
+    /// including the cut showing up in the hand
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.duplicate().is_none()
+    }
+
+    /// the first card that shows up more than once, if any
+    fn duplicate(&self) -> Option<Card> {
+        let all = self.cards();
+        all.iter().enumerate().find(|(i, c)| all[..*i].contains(c)).map(|(_, c)| *c)
+    }
+
+    /// the representative of this hand when the suits are relabeled
+    ///
+    /// Scoring only cares whether cards share a suit and not which suit
+    /// it is, so all hands that are the same up to swapping suits around
+    /// score the same. Like [`CardSet::canonical`], the suits holding
+    /// "larger" sets of ranks in the hand are given the earlier suits and
+    /// the cut is used to break ties between suits holding the same ranks.
+    #[must_use]
+    pub fn canonical(&self) -> Self {
+        let (relabel, _) = relabel_suits(self.suit_keys());
+        let relabel = |c : &Card| Card { suit : relabel[c.suit as usize], rank : c.rank };
+        let hand : Vec<Card> = self.hand.iter().map(relabel).collect();
+        Self::from_cards(&hand, relabel(&self.cut))
+    }
+
+    /// the number of distinct hands that share this hand's canonical form
+    ///
+    /// this is at most 24 (the number of ways to relabel the suits)
+    #[must_use]
+    pub fn class_size(&self) -> usize {
+        relabel_suits(self.suit_keys()).1
+    }
+
+    /// the key used to order the suits when finding the canonical form
+    ///
+    /// the ranks held in the hand are the most important, leaving
+    /// the lowest four bits to hold the rank of the cut (if it is in
+    /// this suit)
+    fn suit_keys(&self) -> [u32; 4] {
+        let set = CardSet::from(&self.hand[..]);
+        ALL_SUITS.map(|s| {
+            let cut = if self.cut.suit == s { self.cut.mask() as u32 + 1 } else { 0 };
+            u32::from(set.suit_ranks(s)) << 4 | cut
+        })
+    }
+
     /// this is where we score cards that _do not_ have a separate cut
     ///
     /// this is helpful for many strategies that pick the cards to keep based
     /// on which ones are cuts
-    pub fn score_nocut(cards : &Vec<&Card>) -> usize {
+    pub fn score_nocut(cards : &[&Card]) -> usize {
         let mut s : usize = 0;
 
         // count points worth fifteen
@@ -115,42 +256,138 @@ impl Hand {
         s
     }
 
+    /// list every combination of cards that scores in this hand
+    ///
+    /// the total of the breakdown is the same as [`Hand::score`] but
+    /// it also holds which cards make up each fifteen, pair, run, flush
+    /// and nobs so that the score can be explained
+    #[must_use]
+    pub fn score_breakdown(&self) -> ScoreBreakdown {
+        self.score_breakdown_as(HandKind::Hand)
+    }
+
+    /// list every combination of cards that scores in this hand
+    /// when it is counted as the input kind of hand
+    #[must_use]
+    pub fn score_breakdown_as(&self, kind : HandKind) -> ScoreBreakdown {
+        self.score_breakdown_with(&ScoringRules::default(), kind)
+    }
+
+    /// list every combination of cards that scores in this hand
+    /// under the input rules when it is counted as the input kind of hand
+    #[must_use]
+    pub fn score_breakdown_with(&self, rules : &ScoringRules, kind : HandKind) -> ScoreBreakdown {
+        ScoreBreakdown::new(&self.hand, self.cut, rules, kind)
+    }
+
     /// this is where we score a hand given a specific cut card
     ///
     /// Points are scored in many different ways:
     /// - Four Card Flush: If the four cards in the hand are the same suit,
-    ///     the player scores four points
+    ///   the player scores four points
     /// - Five Card Flush: If the player has a Four Card Flush and the cut 
-    ///     is the same suit, then the player scores an additional point.
+    ///   is the same suit, then the player scores an additional point.
     /// - Nobs: The player scores a point if they have the Jack of the
-    ///     same suit as the cut card
+    ///   same suit as the cut card
     /// - Fifteens: The player scores two points for all combinations of
-    ///     cards whose values sum to 15 (face cards are all 10).
+    ///   cards whose values sum to 15 (face cards are all 10).
     /// - Runs: The player scores a point for each card participating in
-    ///     a run of three or more cards (face cards maintain their rank).
+    ///   a run of three or more cards (face cards maintain their rank).
     /// - Pairs: The player scores two points for all pairs of cards
-    ///     with the same value.
+    ///   with the same value.
     ///
     /// For Fifteens, Runs, and Pairs, the cut and the player's hand cards
     /// are all treated the same way.
+    ///
+    /// This scores the hand as a player's hand, use [`Hand::score_as`]
+    /// to score it as the crib.
+    #[must_use]
     pub fn score(&self) -> usize {
+        self.score_as(HandKind::Hand)
+    }
+
+    /// score this hand as if it were the input kind of hand
+    ///
+    /// the only difference is the flush, the crib can only score a
+    /// flush if all five cards (including the cut) share a suit
+    #[must_use]
+    pub fn score_as(&self, kind : HandKind) -> usize {
+        self.score_with(&ScoringRules::default(), kind)
+    }
+
+    /// score this hand using the faster, allocation-free scorer
+    ///
+    /// this gives the same score as [`Hand::score`] and is what should
+    /// be used when scoring lots of hands
+    #[must_use]
+    pub fn score_fast(&self) -> usize {
+        self.score_fast_with(&ScoringRules::default(), HandKind::Hand)
+    }
+
+    /// score this hand using the faster, allocation-free scorer
+    /// under the input rules as the input kind of hand
+    ///
+    /// this gives the same score as [`Hand::score_with`]
+    #[must_use]
+    pub fn score_fast_with(&self, rules : &ScoringRules, kind : HandKind) -> usize {
+        score_fast(&self.hand, self.cut, rules, kind)
+    }
+
+    /// the scores of the kept cards over every cut that is still possible
+    ///
+    /// The cut can be any card that isn't in `kept` or `known_cards`
+    /// (the same cards as [`deck::part`] would give), where the known
+    /// cards are the ones a player has seen, like their discards.
+    ///
+    /// ```
+    /// # use cribbage::hand::Hand;
+    /// # use cribbage::cards;
+    /// let dealt = cards!("5H 5C 0C JH 2S 9D");
+    /// let d = Hand::cut_distribution(&[dealt[0], dealt[1], dealt[2], dealt[3]], &dealt[4..]);
+    /// assert_eq!(d.total(), 46);
+    /// assert_eq!(d.max(), Some(20));
+    /// ```
+    #[must_use]
+    pub fn cut_distribution(kept : &[Card;4], known_cards : &[Card]) -> ScoreDistribution {
+        Self::cut_distribution_with(kept, known_cards, &ScoringRules::default(), HandKind::Hand)
+    }
+
+    /// the scores of the kept cards over every cut that is still possible
+    /// under the input rules as the input kind of hand
+    ///
+    /// see [`Hand::cut_distribution`]
+    #[must_use]
+    pub fn cut_distribution_with(kept : &[Card;4], known_cards : &[Card], rules : &ScoringRules, kind : HandKind) -> ScoreDistribution {
+        let seen = CardSet::from(&kept[..]) | CardSet::from(known_cards);
+        deck::remaining(seen)
+            .iter()
+            .map(|cut| score_fast(kept, cut, rules, kind))
+            .collect()
+    }
+
+    /// score this hand under the input rules as the input kind of hand
+    ///
+    /// this is helpful for simulating house rules which change how
+    /// flushes and nobs are counted
+    #[must_use]
+    pub fn score_with(&self, rules : &ScoringRules, kind : HandKind) -> usize {
         // need to calculate score
         let mut s : usize = 0;
 
         // flush
         if self.hand.iter().all(|&c| c.suit == self.hand[0].suit) {
             // there are no cards in the hand that have a different suit than the first card,
-            //  ==> four card flush
-            s += 4;
-            if self.hand[0].suit == self.cut.suit {
-                // 5 card flush
-                s += 1;
+            //  ==> four card flush, the rules decide if it counts with or without the cut
+            let five = self.hand[0].suit == self.cut.suit;
+            if rules.counts_flush(kind, five) {
+                s += if five { 5 } else { 4 };
             }
         }
 
         // player has a Jack matching suit of cut
-        if self.hand.iter().any(|&c| c.suit == self.cut.suit && c.rank == Rank::Jack) {
-            s += 1;
+        let nobs = self.hand.iter().any(|&c| c.suit == self.cut.suit && c.rank == Rank::Jack);
+        if nobs {
+            s += rules.nobs;
         }
 
         // construct full hand for cut-agnostic calculations
@@ -163,7 +400,13 @@ impl Hand {
         ];
         
         // add our cut-necessary and cut-agnotstic calculations together
-        s + Self::score_nocut(&full_hand)
+        s += Self::score_nocut(&full_hand);
+
+        // some house rules give points for a hand with nothing in it
+        if s == 0 && !nobs {
+            s += rules.nineteen_bonus;
+        }
+        s
     }
 }
 
@@ -174,17 +417,153 @@ impl fmt::Display for Hand {
     }
 }
 
-impl std::hash::Hash for Hand {
-    /// hashing a hand is simply hashing all the cards
-    fn hash<H>(&self, state: &mut H)
-    where
-        H: std::hash::Hasher,
-    {
-        self.cut.hash(state);
-        for c in self.hand {
-            c.hash(state);
+impl FromStr for Hand {
+    type Err = HandError;
+    /// parse a hand from a single string
+    ///
+    /// the four cards in the hand are separated from the cut by a '|',
+    /// e.g. "5H 5C 0C JH | QH", and are written in any of the forms
+    /// that [`CardList`] accepts. The form written by `Display`,
+    /// e.g. "[5H 5C 0C JH] QH", is also accepted, with or without the "|".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hand, cut) = s.split_once('|')
+            .or_else(|| s.split_once(']'))
+            .ok_or(HandError::MissingCut)?;
+        // keep track of where the cut starts so positions in errors
+        // are relative to the full string
+        let cut_offset = hand.chars().count() + 1;
+        // swap the brackets for spaces so positions in errors still line up
+        let hand = CardList::from_str(&hand.replace(['[', ']'], " "))?;
+        let cut = CardList::parse_at(cut, cut_offset)?;
+        match cut.0[..] {
+            [cut] => Self::try_new(&hand.0, cut),
+            _ => Err(HandError::WrongCutCount(cut.0.len()))
         }
-        state.finish();
+    }
+}
+
+/// any number of cards held by a player, with or without a cut
+///
+/// [`Hand`] is the usual four cards and a cut while this covers everything
+/// else, like the five cards kept in five-card cribbage or the cards
+/// picked so far while deciding on a discard. Like [`Hand`], the cards
+/// are kept sorted so equality and hashing don't depend on their order.
+///
+/// ```
+/// # use cribbage::hand::Holding;
+/// # use cribbage::cards;
+/// let cs = cards!("7H 8C 9D");
+/// let h = Holding::new([cs[2], cs[0], cs[1]], None);
+/// assert_eq!(h.to_string(), "[7H 9D 8C]");
+/// assert_eq!(h.score(), 5);
+/// ```
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub struct Holding<const N : usize> {
+    cards: [Card; N],
+    cut: Option<Card>,
+}
+
+impl<const N : usize> Holding<N> {
+    /// construct a new holding from its cards and maybe a cut
+    ///
+    /// the cards are not checked for duplicates, use [`Holding::try_new`]
+    /// or [`Holding::is_valid`] if the cards may not come from a single deck
+    #[must_use]
+    pub fn new(mut cards : [Card; N], cut : Option<Card>) -> Self {
+        cards.sort();
+        Self { cards, cut }
+    }
+
+    /// construct a new holding, checking that no card shows up more than once
+    pub fn try_new(cards : [Card; N], cut : Option<Card>) -> Result<Self, HandError> {
+        let holding = Self::new(cards, cut);
+        match holding.duplicate() {
+            Some(c) => Err(HandError::Duplicate(c)),
+            None => Ok(holding)
+        }
+    }
+
+    /// the cards held, sorted
+    #[must_use]
+    pub const fn cards(&self) -> &[Card; N] {
+        &self.cards
+    }
+
+    /// the card that was cut after the deal, if there is one yet
+    #[must_use]
+    pub const fn cut(&self) -> Option<Card> {
+        self.cut
+    }
+
+    /// check that this holding could be dealt from a single deck
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.duplicate().is_none()
+    }
+
+    /// the first card that shows up more than once, if any
+    fn duplicate(&self) -> Option<Card> {
+        let all : Vec<Card> = self.cards.iter().copied().chain(self.cut).collect();
+        all.iter().enumerate().find(|(i, c)| all[..*i].contains(c)).map(|(_, c)| *c)
+    }
+
+    /// score the holding under the standard rules
+    #[must_use]
+    pub fn score(&self) -> usize {
+        self.score_with(&ScoringRules::default(), HandKind::Hand)
+    }
+
+    /// score the holding under the input rules as the input kind of hand
+    ///
+    /// see [`score_cards`] for how flushes and nobs work with
+    /// different numbers of cards
+    #[must_use]
+    pub fn score_with(&self, rules : &ScoringRules, kind : HandKind) -> usize {
+        score_cards(&self.cards, self.cut, rules, kind)
+    }
+}
+
+impl From<Hand> for Holding<4> {
+    fn from(h : Hand) -> Self {
+        Self { cards : h.hand, cut : Some(h.cut) }
+    }
+}
+
+impl<const N : usize> fmt::Display for Holding<N> {
+    /// print the cards in brackets followed by the cut if there is one,
+    /// the same form as [`Hand`]
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.cards.iter().join(" "))?;
+        if let Some(cut) = self.cut {
+            write!(f, " {}", cut)?;
+        }
+        Ok(())
+    }
+}
+
+/// the form a hand takes when serialized
+///
+/// we go through this so that deserializing a hand goes through
+/// [`Hand::try_new`] and checks the cards
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct HandRecord {
+    hand: [Card;4],
+    cut: Card,
+}
+
+#[cfg(feature = "serde")]
+impl From<Hand> for HandRecord {
+    fn from(h : Hand) -> Self {
+        Self { hand: h.hand, cut: h.cut }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<HandRecord> for Hand {
+    type Error = HandError;
+    fn try_from(r : HandRecord) -> Result<Self, Self::Error> {
+        Self::try_new(&r.hand, r.cut)
     }
 }
 
@@ -238,6 +617,25 @@ mod tests {
             )
     }
 
+    #[test]
+    fn diff_order_in_map() {
+        let mut scores = std::collections::HashMap::new();
+        scores.insert(Hand::new(&["5H", "5C", "0C", "JH"], "QH"), 18);
+        assert_eq!(scores.get(&Hand::new(&["JH", "0C", "5H", "5C"], "QH")), Some(&18));
+        assert_eq!(scores.get(&Hand::new(&["5H", "5C", "0C", "QH"], "JH")), None);
+    }
+
+    #[test]
+    fn validity() {
+        assert!(Hand::new(&["2H", "3H", "4H", "5H"],"6H").is_valid());
+        assert!(!Hand::new(&["2H", "3H", "4H", "5H"],"5H").is_valid());
+        assert!(!Hand::new(&["2H", "3H", "3H", "5H"],"6H").is_valid());
+        let h = Hand::new(&["5H", "2H", "4H", "3H"],"6H");
+        assert_eq!(h.hand()[0].to_string(), "2H");
+        assert!(h.hand().windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(h.cut().to_string(), "6H");
+    }
+
     #[test]
     #[should_panic]
     fn diff_hash_because_cut() {
@@ -247,6 +645,90 @@ mod tests {
             )
     }
 
+    #[test]
+    fn hand_from_str() {
+        let h = Hand::new(&["5H", "5C", "0C", "JH"], "QH");
+        assert_eq!(Hand::from_str("5H 5C 0C JH | QH"), Ok(h));
+        assert_eq!(Hand::from_str("5H5C10CJH|QH"), Ok(h));
+        assert_eq!(Hand::from_str("jh, 0c, 5c, 5h | queen of hearts"), Ok(h));
+        assert_eq!(Hand::from_str(&h.to_string()), Ok(h));
+        assert_eq!(Hand::from_str("[5H 5C 0C JH] | QH"), Ok(h));
+        assert_eq!(Hand::from_str("[5H 5C 0C JH]|QH"), Ok(h));
+    }
+
+    #[test]
+    fn hand_from_str_errors() {
+        assert_eq!(Hand::from_str("5H 5C 0C JH QH"), Err(HandError::MissingCut));
+        assert_eq!(Hand::from_str("5H 5C 0C | QH"), Err(HandError::WrongCount(3)));
+        assert_eq!(Hand::from_str("5H 5C 0C JH | QH KH"), Err(HandError::WrongCutCount(2)));
+        assert_eq!(Hand::from_str("5H 5C 0C JH | 5H"), Err(HandError::Duplicate(Card::from_str("5H").unwrap())));
+        assert_eq!(Hand::from_str("5H 5C 0C JH | QX"),
+            Err(HandError::Parse(CardParseError::UnknownSuit('X').at(14))));
+    }
+
+    #[test]
+    fn try_new_duplicates() {
+        let cards = crate::cards!("5H 5C 5H JH");
+        assert_eq!(Hand::try_new(&cards, cards[3]), Err(HandError::Duplicate(cards[0])));
+        assert_eq!(Hand::try_new(&cards[1..], cards[0]), Err(HandError::WrongCount(3)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_json_round_trip() {
+        let h = Hand::new(&["5H", "5C", "0C", "JH"], "QH");
+        let json = serde_json::to_string(&h).unwrap();
+        assert_eq!(json, r#"{"hand":["5H","JH","5C","0C"],"cut":"QH"}"#);
+        assert_eq!(serde_json::from_str::<Hand>(&json).unwrap(), h);
+        assert!(serde_json::from_str::<Hand>(r#"{"hand":["5H","5C","0C"],"cut":"QH"}"#).is_err());
+        assert!(serde_json::from_str::<Hand>(r#"{"hand":["5H","5C","0C","QH"],"cut":"QH"}"#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_toml_round_trip() {
+        let h = Hand::new(&["AH", "2C", "3D", "4D"], "6H");
+        let s = toml::to_string(&h).unwrap();
+        assert_eq!(toml::from_str::<Hand>(&s).unwrap(), h);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_csv_round_trip() {
+        let hands = [
+            Hand::new(&["AH", "2C", "3D", "4D"], "6H"),
+            Hand::new(&["5H", "5C", "0C", "JH"], "QH"),
+        ];
+        let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        for h in hands {
+            wtr.serialize(h).unwrap();
+        }
+        let data = wtr.into_inner().unwrap();
+        assert_eq!(String::from_utf8(data.clone()).unwrap(), "AH,3D,4D,2C,6H\n5H,JH,5C,0C,QH\n");
+        let mut rdr = csv::ReaderBuilder::new().has_headers(false).from_reader(&data[..]);
+        let read : Vec<Hand> = rdr.deserialize().collect::<Result<_,_>>().unwrap();
+        assert_eq!(read, hands);
+    }
+
+    #[test]
+    fn canonical_under_relabeling() {
+        use crate::card::Suit;
+        let h = Hand::new(&["5D", "5C", "0C", "JD"], "QD");
+        let canon = h.canonical();
+        assert_eq!(canon, Hand::new(&["5H", "JH", "5S", "0S"], "QH"));
+        assert_eq!(canon.score(), h.score());
+        let swapped = |s : Suit| match s {
+            Suit::Club => Suit::Heart,
+            Suit::Heart => Suit::Club,
+            s => s
+        };
+        let cards : Vec<Card> = h.hand().iter().map(|c| Card { suit : swapped(c.suit), rank : c.rank }).collect();
+        let other = Hand::from_cards(&cards, Card { suit : swapped(h.cut().suit), rank : h.cut().rank });
+        assert_eq!(other.canonical(), canon);
+        assert_eq!(h.class_size(), 12);
+        assert_eq!(Hand::new(&["5H", "5S", "5D", "5C"], "JH").class_size(), 4);
+    }
+
     fn test_score(h : &[&str], c : &str) -> usize {
         Hand::new(h,c).score()
     }
@@ -261,6 +743,43 @@ mod tests {
         assert_eq!(test_score(&["2H","4H","6H","8H"],"0C"), 4);
     }
 
+    #[test]
+    fn crib_four_flush_does_not_count() {
+        let h = Hand::new(&["2H","4H","6H","8H"],"0C");
+        assert_eq!(h.score_as(HandKind::Crib), 0);
+        assert_eq!(h.score_as(HandKind::Hand), 4);
+        assert_eq!(h.score_breakdown_as(HandKind::Crib).total(), 0);
+    }
+
+    #[test]
+    fn crib_five_flush_counts() {
+        let h = Hand::new(&["2H","4H","6H","8H"],"0H");
+        assert_eq!(h.score_as(HandKind::Crib), 5);
+        assert_eq!(h.score_breakdown_as(HandKind::Crib).total(), 5);
+    }
+
+    #[test]
+    fn house_rules() {
+        use crate::score::CribFlush;
+        let rules = ScoringRules {
+            four_card_flush : false,
+            crib_flush : CribFlush::SameAsHand,
+            nobs : 2,
+            nineteen_bonus : 3,
+            ..ScoringRules::default()
+        };
+        let four_flush = Hand::new(&["2H","4H","6H","8H"],"0C");
+        assert_eq!(four_flush.score_with(&rules, HandKind::Hand), 3);
+        assert_eq!(four_flush.score_with(&rules, HandKind::Crib), 3);
+        assert_eq!(Hand::new(&["2H","4H","6H","8H"],"0H").score_with(&rules, HandKind::Crib), 5);
+        let nobs = Hand::new(&["3H","5C","JH","QH"],"7H");
+        assert_eq!(nobs.score_with(&rules, HandKind::Hand), 8);
+        assert_eq!(nobs.score_breakdown_with(&rules, HandKind::Hand).total(), 8);
+        let crib_rules = ScoringRules { crib_flush : CribFlush::Never, ..ScoringRules::default() };
+        assert_eq!(Hand::new(&["2H","4H","6H","8H"],"0H").score_with(&crib_rules, HandKind::Crib), 0);
+        assert_eq!(Hand::new(&["2H","4H","6H","8H"],"0H").score_with(&crib_rules, HandKind::Hand), 5);
+    }
+
     #[test]
     fn score_flush_fifteens() {
         assert_eq!(test_score(&["2H","3H","5H","TH"],"5C"), 14)
@@ -340,4 +859,32 @@ mod tests {
     fn five_card_fifteen() {
         assert_eq!(test_score(&["AC","2H","3H","4D"],"5C"),7);
     }
+
+    #[test]
+    fn cut_distribution_matches_part() {
+        let dealt = crate::cards!("5H 5C 0C JH 2S 9D");
+        let kept = [dealt[0], dealt[1], dealt[2], dealt[3]];
+        let d = Hand::cut_distribution(&kept, &dealt[4..]);
+        let scores : Vec<usize> = deck::part(&dealt).iter().map(|&cut| Hand::from_cards(&kept, cut).score()).collect();
+        assert_eq!(d.total(), 46);
+        assert_eq!(d.total(), scores.len() as u64);
+        assert_eq!(d.min(), scores.iter().copied().min());
+        assert_eq!(d.max(), scores.iter().copied().max());
+        assert_eq!(d.mean(), scores.iter().sum::<usize>() as f64 / 46.0);
+    }
+
+    #[test]
+    fn holding_matches_hand() {
+        let h = Hand::from_str("5H 5C 0C JH | QH").unwrap();
+        let holding = Holding::from(h);
+        assert_eq!(holding.score(), h.score());
+        assert_eq!(holding.to_string(), h.to_string());
+    }
+
+    #[test]
+    fn holding_duplicate() {
+        let cs = crate::cards!("5H 5C 0C JH 4S 5C");
+        assert_eq!(Holding::try_new([cs[0], cs[1], cs[2], cs[3], cs[4]], Some(cs[5])), Err(HandError::Duplicate(cs[1])));
+        assert!(Holding::try_new([cs[0], cs[1], cs[2]], None).is_ok());
+    }
 }
@@ -1,4 +1,10 @@
 pub mod card;
+pub mod cardset;
 pub mod hand;
 pub mod deck;
+pub mod score;
 pub mod strategy;
+pub mod stats;
+pub mod muggins;
+pub mod lut;
+pub mod crib_table;
@@ -0,0 +1,350 @@
+use crate::cardset::CardSet;
+use crate::hand::Hand;
+use crate::score::{ score_fast, CribFlush, HandKind, ScoringRules };
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{ self, Write };
+use std::path::Path;
+use std::{ error, fmt };
+
+/// the number of hands in a table, one for each four cards and cut
+pub const TABLE_LEN : usize = FOUR_CARD_SETS * 48;
+
+/// the bytes at the start of every table file
+pub const MAGIC : [u8; 8] = *b"CRIBLUT\0";
+
+/// the version of the file format written by [`write_table`]
+pub const VERSION : u16 = 1;
+
+/// the number of bytes before the scores start
+///
+/// the header is laid out as
+/// - `0..8` [`MAGIC`]
+/// - `8..10` [`VERSION`] as a little-endian integer
+/// - `10` the kind of hand, 0 for a hand and 1 for the crib
+/// - `11` if a four card flush counts in a hand (0 or 1)
+/// - `12` the crib flush policy, 0 for five card only, 1 for same as hand, 2 for never
+/// - `13` the points for nobs
+/// - `14` the points for a nineteen hand
+/// - `15` unused, always 0
+pub const HEADER_LEN : usize = 16;
+
+/// the number of ways to choose four cards from the deck
+const FOUR_CARD_SETS : usize = 270_725;
+
+/// the ways loading a table can go wrong
+///
+/// this is shared by every kind of table file the crate writes,
+/// including [`crate::crib_table::CribTable`]
+#[derive(Debug)]
+pub enum LutError {
+    /// the file could not be opened or mapped
+    Io(io::Error),
+    /// the file does not start with the magic bytes for its kind of table
+    BadMagic,
+    /// the file was written with a version of the format we can't read
+    UnsupportedVersion(u16),
+    /// the header holds a value that doesn't mean anything
+    BadHeader(&'static str),
+    /// the file is not as long as its kind of table should be
+    WrongLength {
+        /// the number of bytes the table should have
+        expected : usize,
+        /// the number of bytes the file has
+        actual : usize,
+    },
+}
+
+impl fmt::Display for LutError {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::BadMagic => write!(f, "not a cribbage table"),
+            Self::UnsupportedVersion(v) => write!(f, "table version {} is not supported", v),
+            Self::BadHeader(field) => write!(f, "table header has a bad {}", field),
+            Self::WrongLength { expected, actual } => write!(f, "table should be {} bytes but is {}", expected, actual),
+        }
+    }
+}
+
+impl error::Error for LutError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LutError {
+    fn from(e : io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// the position of a hand in a score table
+///
+/// This is a perfect hash of the hand into `0..TABLE_LEN`. The four cards
+/// are ranked among all sets of four cards by the combinatorial number
+/// system (the colexicographic order of their [`crate::card::Card::index`])
+/// and the cut is ranked among the 48 cards left in the deck.
+///
+/// There is no position for a hand that isn't valid (see [`Hand::is_valid`]),
+/// like one where the cut is also in the hand, so this gives `None` for them.
+#[must_use]
+pub fn hand_index(hand : &Hand) -> Option<usize> {
+    if !hand.is_valid() {
+        return None;
+    }
+    let mut set_rank = 0;
+    let mut below_cut = 0;
+    let cut = hand.cut().index();
+    let mut indices = hand.hand().map(|c| c.index());
+    indices.sort_unstable();
+    for (i, &c) in indices.iter().enumerate() {
+        set_rank += choose(c, i + 1);
+        if c < cut {
+            below_cut += 1;
+        }
+    }
+    Some(set_rank * 48 + cut - below_cut)
+}
+
+/// the number of ways to choose `k` items from `n`
+const fn choose(n : usize, k : usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    let mut out = 1;
+    let mut i = 0;
+    while i < k {
+        out = out * (n - i) / (i + 1);
+        i += 1;
+    }
+    out
+}
+
+/// write the header for a table of the input kind of hand under the input rules
+fn header(rules : &ScoringRules, kind : HandKind) -> io::Result<[u8; HEADER_LEN]> {
+    let small = |n : usize, what : &str| u8::try_from(n)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, format!("{} points are too large for a score table", what)));
+    let mut h = [0; HEADER_LEN];
+    h[..8].copy_from_slice(&MAGIC);
+    h[8..10].copy_from_slice(&VERSION.to_le_bytes());
+    h[10] = match kind { HandKind::Hand => 0, HandKind::Crib => 1 };
+    h[11] = u8::from(rules.four_card_flush);
+    h[12] = match rules.crib_flush {
+        CribFlush::FiveCardOnly => 0,
+        CribFlush::SameAsHand => 1,
+        CribFlush::Never => 2,
+    };
+    h[13] = small(rules.nobs, "nobs")?;
+    h[14] = small(rules.nineteen_bonus, "nineteen hand")?;
+    Ok(h)
+}
+
+/// check the parts of a header every kind of table file starts with:
+/// its length, the magic bytes and the version as a little-endian integer
+pub(crate) fn check_file(bytes : &[u8], magic : &[u8; 8], version : u16, len : usize) -> Result<(), LutError> {
+    if bytes.len() != len {
+        return Err(LutError::WrongLength { expected : len, actual : bytes.len() });
+    }
+    if bytes[..8] != magic[..] {
+        return Err(LutError::BadMagic);
+    }
+    let found = u16::from_le_bytes([bytes[8], bytes[9]]);
+    if found != version {
+        return Err(LutError::UnsupportedVersion(found));
+    }
+    Ok(())
+}
+
+/// read the kind of hand and rules from the header of a table
+fn parse_header(bytes : &[u8]) -> Result<(ScoringRules, HandKind), LutError> {
+    check_file(bytes, &MAGIC, VERSION, HEADER_LEN + TABLE_LEN)?;
+    let kind = match bytes[10] {
+        0 => HandKind::Hand,
+        1 => HandKind::Crib,
+        _ => return Err(LutError::BadHeader("kind of hand")),
+    };
+    let four_card_flush = match bytes[11] {
+        0 => false,
+        1 => true,
+        _ => return Err(LutError::BadHeader("four card flush")),
+    };
+    let crib_flush = match bytes[12] {
+        0 => CribFlush::FiveCardOnly,
+        1 => CribFlush::SameAsHand,
+        2 => CribFlush::Never,
+        _ => return Err(LutError::BadHeader("crib flush")),
+    };
+    let rules = ScoringRules {
+        four_card_flush,
+        crib_flush,
+        nobs : bytes[13].into(),
+        nineteen_bonus : bytes[14].into(),
+        ..ScoringRules::default()
+    };
+    Ok((rules, kind))
+}
+
+/// the score of every hand in table order, one byte per hand
+///
+/// # Errors
+/// - if any score doesn't fit in a byte
+pub fn scores(rules : &ScoringRules, kind : HandKind) -> io::Result<Vec<u8>> {
+    let mut table = vec![0; TABLE_LEN];
+    for kept in CardSet::full().combinations(4) {
+        let cards : [_; 4] = kept.iter().collect::<Vec<_>>().try_into().unwrap();
+        for cut in kept.complement() {
+            let hand = Hand::from_cards(&cards, cut);
+            let score = score_fast(hand.hand(), cut, rules, kind);
+            let i = hand_index(&hand).expect("every hand dealt from the deck is valid");
+            table[i] = u8::try_from(score)
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "score is too large for a score table"))?;
+        }
+    }
+    Ok(table)
+}
+
+/// write a complete table file scoring the input kind of hand under the input rules
+///
+/// # Errors
+/// - if writing fails or the rules allow scores that don't fit in a byte
+pub fn write_table<W : Write>(mut w : W, rules : &ScoringRules, kind : HandKind) -> io::Result<()> {
+    w.write_all(&header(rules, kind)?)?;
+    w.write_all(&scores(rules, kind)?)?;
+    w.flush()
+}
+
+/// a table of the score of every hand, memory-mapped from a file
+///
+/// Opening the table only maps the file, so scoring a hand is a single
+/// lookup and the parts of the file that are never used are never read.
+///
+/// ```no_run
+/// # use cribbage::lut::ScoreTable;
+/// # use cribbage::hand::Hand;
+/// # use std::str::FromStr;
+/// let table = ScoreTable::open("scores.lut").unwrap();
+/// let hand = Hand::from_str("5H 5C 5D JS | 5S").unwrap();
+/// assert_eq!(table.score(&hand), Some(29));
+/// ```
+#[derive(Debug)]
+pub struct ScoreTable {
+    map : Mmap,
+    rules : ScoringRules,
+    kind : HandKind,
+}
+
+impl ScoreTable {
+    /// map a table file written by [`write_table`]
+    ///
+    /// # Errors
+    /// - if the file can't be opened or mapped or isn't a valid table
+    pub fn open<P : AsRef<Path>>(path : P) -> Result<Self, LutError> {
+        let file = File::open(path)?;
+        // SAFETY: the table is only ever read and we don't expect
+        //  anyone to change the file while it is mapped
+        let map = unsafe { Mmap::map(&file)? };
+        let (rules, kind) = parse_header(&map)?;
+        Ok(Self { map, rules, kind })
+    }
+
+    /// the score of the input hand
+    ///
+    /// this is `None` if the hand isn't valid (see [`Hand::is_valid`])
+    /// since the table only holds hands that can be dealt
+    #[must_use]
+    pub fn score(&self, hand : &Hand) -> Option<usize> {
+        hand_index(hand).map(|i| self.map[HEADER_LEN + i].into())
+    }
+
+    /// the rules the hands were scored under
+    ///
+    /// only the rules that affect the score of a hand are stored,
+    /// the others are left as their default
+    #[must_use]
+    pub const fn rules(&self) -> &ScoringRules {
+        &self.rules
+    }
+
+    /// the kind of hand that was scored
+    #[must_use]
+    pub const fn kind(&self) -> HandKind {
+        self.kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn index_is_perfect_hash() {
+        let mut seen = vec![false; FOUR_CARD_SETS];
+        for kept in CardSet::full().combinations(4) {
+            let cards : Vec<_> = kept.iter().collect();
+            // the lowest and highest cuts give the ends of each block of 48
+            let cuts : Vec<_> = kept.complement().iter().collect();
+            let first = hand_index(&Hand::from_cards(&cards, cuts[0])).unwrap();
+            let last = hand_index(&Hand::from_cards(&cards, cuts[47])).unwrap();
+            assert_eq!(first % 48, 0);
+            assert_eq!(last, first + 47);
+            assert!(!seen[first / 48]);
+            seen[first / 48] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn index_ignores_order() {
+        let a = Hand::from_str("5H 5C 0C JH | QH").unwrap();
+        let b = Hand::from_str("JH 0C 5C 5H | QH").unwrap();
+        assert_eq!(hand_index(&a), hand_index(&b));
+        assert_eq!(hand_index(&Hand::from_str("AH AS AD AC | 2H").unwrap()), Some(0));
+        assert_eq!(hand_index(&Hand::from_str("KH KS KD KC | QC").unwrap()), Some(TABLE_LEN - 1));
+    }
+
+    #[test]
+    fn duplicated_cut_has_no_score() {
+        // the cut duplicating the last card would land one past the end of the table
+        let h = Hand::new(&["KH","KS","KD","KC"],"KC");
+        assert_eq!(hand_index(&h), None);
+
+        // an empty table is enough to check the lookup doesn't go out of bounds
+        let path = std::env::temp_dir().join("cribbage-lut-invalid-test.lut");
+        let mut bytes = header(&ScoringRules::default(), HandKind::Hand).unwrap().to_vec();
+        bytes.resize(HEADER_LEN + TABLE_LEN, 0);
+        std::fs::write(&path, bytes).unwrap();
+        let table = ScoreTable::open(&path).unwrap();
+        assert_eq!(table.score(&h), None);
+        assert_eq!(table.score(&Hand::new(&["KH","KS","KD","KC"],"QC")), Some(0));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn header_round_trip() {
+        let rules = ScoringRules { four_card_flush : false, crib_flush : CribFlush::Never, nobs : 2, nineteen_bonus : 1, ..ScoringRules::default() };
+        let mut bytes = header(&rules, HandKind::Crib).unwrap().to_vec();
+        bytes.resize(HEADER_LEN + TABLE_LEN, 0);
+        assert_eq!(parse_header(&bytes).unwrap(), (rules, HandKind::Crib));
+        bytes[0] = b'X';
+        assert!(matches!(parse_header(&bytes), Err(LutError::BadMagic)));
+        assert!(matches!(parse_header(&bytes[..100]), Err(LutError::WrongLength { actual : 100, .. })));
+    }
+
+    #[test]
+    #[ignore = "slow, run with `cargo test --release -- --ignored`"]
+    fn table_matches_score() {
+        let path = std::env::temp_dir().join("cribbage-lut-test.lut");
+        write_table(io::BufWriter::new(File::create(&path).unwrap()), &ScoringRules::default(), HandKind::Hand).unwrap();
+        let table = ScoreTable::open(&path).unwrap();
+        assert_eq!(table.kind(), HandKind::Hand);
+        for (hand, _) in crate::deck::canonical_hands() {
+            assert_eq!(table.score(&hand), Some(hand.score()), "{}", hand);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+}
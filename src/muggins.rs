@@ -0,0 +1,307 @@
+use crate::cardset::CardSet;
+use crate::hand::Hand;
+use crate::score::{ Combo, HandKind, ScoreBreakdown, ScoringRules };
+use std::fmt;
+use std::mem::discriminant;
+
+/// how a player's claimed score compares to what their hand is worth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    /// the player counted their hand correctly
+    Correct,
+    /// the player missed this many points, which their opponent may
+    /// claim for themselves when playing muggins
+    Missed(usize),
+    /// the player claimed this many more points than the hand is worth
+    Overclaimed(usize),
+}
+
+/// the result of checking a player's count of their hand
+///
+/// In muggins, any points a player misses when counting their hand can
+/// be claimed by their opponent. A count can be checked against either
+/// the claimed score alone ([`Judgement::from_score`]) or the list of
+/// combinations the player announced ([`Judgement::from_combos`]).
+/// Only the second can always say exactly which combinations were missed
+/// since many different sets of combinations can add up to the same score,
+/// with just a score [`Judgement::candidates`] gives each set that could
+/// make up the missing points.
+///
+/// ```
+/// # use cribbage::muggins::{Judgement, Verdict};
+/// # use cribbage::score::{Combo, ScoringRules, HandKind};
+/// # use cribbage::hand::Hand;
+/// # use cribbage::cards;
+/// # use std::str::FromStr;
+/// let hand = Hand::from_str("7H 8C 2D 2S | KH").unwrap();
+/// let claimed = [Combo::Pair([cards!("2D")[0], cards!("2S")[0]])];
+/// let j = Judgement::from_combos(&hand, &claimed, &ScoringRules::default(), HandKind::Hand);
+/// assert_eq!(j.verdict(), Verdict::Missed(2));
+/// assert_eq!(j.to_string(), "missed a fifteen: 7H 8C");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Judgement {
+    breakdown : ScoreBreakdown,
+    claimed : usize,
+    missed : Vec<Combo>,
+    bogus : Vec<Combo>,
+    combos_claimed : bool,
+}
+
+impl Judgement {
+    /// check a claimed score for the hand
+    ///
+    /// Which combinations were missed can't always be known from the
+    /// score alone, so [`Judgement::missed`] only has the combinations
+    /// that are in every one of the [`Judgement::candidates`], which is
+    /// all of them when there is only one way to make up the shortfall.
+    ///
+    /// ```
+    /// # use cribbage::muggins::{Judgement, Verdict};
+    /// # use cribbage::score::{Combo, ScoringRules, HandKind};
+    /// # use cribbage::hand::Hand;
+    /// # use cribbage::cards;
+    /// # use std::str::FromStr;
+    /// let hand = Hand::from_str("5H 5C 0C JH | QH").unwrap();
+    /// let j = Judgement::from_score(&hand, 17, &ScoringRules::default(), HandKind::Hand);
+    /// assert_eq!(j.verdict(), Verdict::Missed(1));
+    /// assert_eq!(j.missed(), &[Combo::Nobs(cards!("JH")[0])]);
+    /// ```
+    #[must_use]
+    pub fn from_score(hand : &Hand, claimed : usize, rules : &ScoringRules, kind : HandKind) -> Self {
+        let mut j = Self {
+            breakdown : hand.score_breakdown_with(rules, kind),
+            claimed,
+            missed : Vec::new(),
+            bogus : Vec::new(),
+            combos_claimed : false,
+        };
+        let candidates = j.candidates();
+        if let Some((first, rest)) = candidates.split_first() {
+            j.missed = first
+                .iter()
+                .filter(|c| rest.iter().all(|other| other.contains(c)))
+                .cloned()
+                .collect();
+        }
+        j
+    }
+
+    /// check the combinations a player announced while counting the hand
+    ///
+    /// Combinations are matched by what kind they are and which cards
+    /// are in them, so the order the cards are listed doesn't matter.
+    /// Each combination in the hand can only be counted once, a claimed
+    /// combination that doesn't match one in the hand (or repeats one
+    /// already counted) is listed in [`Judgement::bogus`].
+    #[must_use]
+    pub fn from_combos(hand : &Hand, claimed : &[Combo], rules : &ScoringRules, kind : HandKind) -> Self {
+        let breakdown = hand.score_breakdown_with(rules, kind);
+        let mut missed = breakdown.combos().to_vec();
+        let mut bogus = Vec::new();
+        for c in claimed {
+            match missed.iter().position(|m| same_combo(m, c)) {
+                Some(i) => { missed.remove(i); },
+                None => bogus.push(c.clone()),
+            }
+        }
+        let claimed = claimed.iter().map(|c| c.points_with(rules)).sum();
+        Self { breakdown, claimed, missed, bogus, combos_claimed : true }
+    }
+
+    /// how the claimed score compares to the actual score
+    #[must_use]
+    pub fn verdict(&self) -> Verdict {
+        let actual = self.breakdown.total();
+        if self.claimed < actual {
+            Verdict::Missed(actual - self.claimed)
+        } else if self.claimed > actual {
+            Verdict::Overclaimed(self.claimed - actual)
+        } else {
+            Verdict::Correct
+        }
+    }
+
+    /// every scoring combination in the hand
+    #[must_use]
+    pub const fn breakdown(&self) -> &ScoreBreakdown {
+        &self.breakdown
+    }
+
+    /// the score the player claimed
+    #[must_use]
+    pub const fn claimed(&self) -> usize {
+        self.claimed
+    }
+
+    /// the combinations in the hand the player did not count
+    #[must_use]
+    pub fn missed(&self) -> &[Combo] {
+        &self.missed
+    }
+
+    /// the combinations the player counted that aren't in the hand
+    #[must_use]
+    pub fn bogus(&self) -> &[Combo] {
+        &self.bogus
+    }
+
+    /// each set of combinations in the hand whose points add up to
+    /// the points the player missed
+    ///
+    /// this is what an opponent could point to when settling a dispute
+    /// over a claimed score, it is empty when the player didn't miss
+    /// anything and combinations worth no points are left out
+    #[must_use]
+    pub fn candidates(&self) -> Vec<Vec<Combo>> {
+        let shortfall = match self.verdict() {
+            Verdict::Missed(n) => n,
+            _ => return Vec::new(),
+        };
+        let rules = self.breakdown.rules();
+        let scoring : Vec<&Combo> = self.breakdown
+            .combos()
+            .iter()
+            .filter(|c| c.points_with(rules) > 0)
+            .collect();
+        let mut found = Vec::new();
+        subsets_worth(&scoring, rules, shortfall, &mut Vec::new(), &mut found);
+        found
+    }
+}
+
+/// add each subset of the combinations worth exactly `points` to `found`,
+/// every subset being extended from the combinations in `chosen`
+fn subsets_worth(combos : &[&Combo], rules : &ScoringRules, points : usize, chosen : &mut Vec<Combo>, found : &mut Vec<Vec<Combo>>) {
+    if points == 0 {
+        found.push(chosen.clone());
+        return;
+    }
+    for (i, c) in combos.iter().enumerate() {
+        let p = c.points_with(rules);
+        if p <= points {
+            chosen.push((*c).clone());
+            subsets_worth(&combos[i + 1..], rules, points - p, chosen, found);
+            chosen.pop();
+        }
+    }
+}
+
+impl fmt::Display for Judgement {
+    /// list what the player got wrong, one item per line,
+    /// e.g. "missed a fifteen: 7H 8C"
+    ///
+    /// when only a score was claimed this says how many points were
+    /// missed or overclaimed, and if the missed combinations can't be
+    /// pinned down it lists every combination in the hand to check against
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = Vec::new();
+        if let Verdict::Missed(n) = self.verdict() {
+            let rules = self.breakdown.rules();
+            let known : usize = self.missed.iter().map(|c| c.points_with(rules)).sum();
+            if !self.combos_claimed && known < n {
+                lines.push(format!("missed {} points out of", n));
+                lines.extend(self.breakdown.combos().iter().map(|c| format!("  {}", c)));
+                return write!(f, "{}", lines.join("\n"));
+            }
+        }
+        lines.extend(self.missed.iter().map(|c| format!("missed {}", c)));
+        lines.extend(self.bogus.iter().map(|c| format!("wrongly claimed {}", c)));
+        if lines.is_empty() {
+            lines.push(match self.verdict() {
+                Verdict::Correct => "correct".to_string(),
+                Verdict::Missed(n) => format!("missed {} points", n),
+                Verdict::Overclaimed(n) => format!("claimed {} points too many", n),
+            });
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// check if two combinations are the same kind and hold the same cards
+fn same_combo(a : &Combo, b : &Combo) -> bool {
+    discriminant(a) == discriminant(b)
+        && a.cards().len() == b.cards().len()
+        && CardSet::from(a.cards()) == CardSet::from(b.cards())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards;
+    use std::str::FromStr;
+
+    fn judge(h : &str, claimed : &[Combo]) -> Judgement {
+        Judgement::from_combos(&Hand::from_str(h).unwrap(), claimed, &ScoringRules::default(), HandKind::Hand)
+    }
+
+    #[test]
+    fn everything_counted() {
+        let hand = Hand::from_str("5H 5C 0C JH | QH").unwrap();
+        let j = judge("5H 5C 0C JH | QH", hand.score_breakdown().combos());
+        assert_eq!(j.verdict(), Verdict::Correct);
+        assert!(j.missed().is_empty());
+        assert_eq!(j.to_string(), "correct");
+    }
+
+    #[test]
+    fn card_order_does_not_matter() {
+        let j = judge("7H 8C 2D 2S | KH", &[Combo::Fifteen(cards!("8C 7H")), Combo::Pair([cards!("2S")[0], cards!("2D")[0]])]);
+        assert_eq!(j.verdict(), Verdict::Correct);
+    }
+
+    #[test]
+    fn missed_run() {
+        let j = judge("3H 4D 4C 5C | 7H", &[Combo::Run(cards!("3H 4D 5C")), Combo::Pair([cards!("4D")[0], cards!("4C")[0]])]);
+        assert_eq!(j.verdict(), Verdict::Missed(7));
+        assert!(j.missed().contains(&Combo::Run(cards!("3H 4C 5C"))));
+        assert!(j.bogus().is_empty());
+    }
+
+    #[test]
+    fn bogus_claims() {
+        let j = judge("7H 8C 2D 2S | KH", &[Combo::Fifteen(cards!("7H 8C")), Combo::Fifteen(cards!("7H 8C"))]);
+        assert_eq!(j.bogus(), &[Combo::Fifteen(cards!("7H 8C"))]);
+        assert_eq!(j.to_string(), "missed a pair: 2S 2D\nwrongly claimed a fifteen: 7H 8C");
+    }
+
+    #[test]
+    fn claimed_score() {
+        let hand = Hand::from_str("5H 5C 0C JH | QH").unwrap();
+        let j = Judgement::from_score(&hand, 14, &ScoringRules::default(), HandKind::Hand);
+        assert_eq!(j.verdict(), Verdict::Missed(4));
+        assert!(j.missed().is_empty());
+        assert_eq!(j.to_string().lines().next(), Some("missed 4 points out of"));
+        assert_eq!(j.to_string().lines().count(), 1 + hand.score_breakdown().combos().len());
+        // two of the six fifteens, a fifteen and the pair, or the run and nobs
+        let candidates = j.candidates();
+        assert_eq!(candidates.len(), 15 + 6 + 1);
+        assert!(candidates.contains(&vec![Combo::Run(cards!("0C JH QH")), Combo::Nobs(cards!("JH")[0])]));
+        let j = Judgement::from_score(&hand, 20, &ScoringRules::default(), HandKind::Hand);
+        assert_eq!(j.verdict(), Verdict::Overclaimed(2));
+        assert!(j.candidates().is_empty());
+    }
+
+    #[test]
+    fn claimed_score_with_one_explanation() {
+        let hand = Hand::from_str("7H 8C 2D 2S | KH").unwrap();
+        let j = Judgement::from_score(&hand, 2, &ScoringRules::default(), HandKind::Hand);
+        assert_eq!(j.candidates(), vec![vec![Combo::Fifteen(cards!("7H 8C"))], vec![Combo::Pair([cards!("2S")[0], cards!("2D")[0]])]]);
+        assert!(j.missed().is_empty());
+        assert_eq!(j.to_string(), "missed 2 points out of\n  a fifteen: 7H 8C\n  a pair: 2S 2D");
+
+        let hand = Hand::from_str("5H 5C 0C JH | QH").unwrap();
+        let j = Judgement::from_score(&hand, 17, &ScoringRules::default(), HandKind::Hand);
+        assert_eq!(j.candidates(), vec![vec![Combo::Nobs(cards!("JH")[0])]]);
+        assert_eq!(j.to_string(), "missed nobs: JH");
+    }
+
+    #[test]
+    fn agrees_with_scorer_on_nineteen_bonus() {
+        let rules = ScoringRules { nobs : 0, nineteen_bonus : 3, ..ScoringRules::default() };
+        let hand = Hand::from_str("JH 2C 4D 8S | 6H").unwrap();
+        let claimed = hand.score_with(&rules, HandKind::Hand);
+        let j = Judgement::from_score(&hand, claimed, &rules, HandKind::Hand);
+        assert_eq!(j.verdict(), Verdict::Correct);
+    }
+}
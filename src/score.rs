@@ -0,0 +1,577 @@
+use crate::card::{ Rank, Card };
+use std::fmt;
+use itertools::Itertools;
+
+/// which kind of hand is being scored
+///
+/// the crib is scored slightly differently than a player's hand
+/// since a flush in the crib must include the cut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HandKind {
+    /// the four cards kept by a player
+    #[default]
+    Hand,
+    /// the four cards discarded into the crib and scored by the dealer
+    Crib,
+}
+
+/// which flushes count when scoring the crib
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CribFlush {
+    /// only a flush of all five cards (including the cut) counts
+    #[default]
+    FiveCardOnly,
+    /// flushes in the crib count the same way they do in a hand
+    SameAsHand,
+    /// flushes never count in the crib
+    Never,
+}
+
+/// the rules used when scoring a hand
+///
+/// The default follows the standard rules of cribbage while
+/// the fields can be changed to simulate house variants.
+///
+/// ```
+/// # use cribbage::score::{ScoringRules, HandKind};
+/// # use cribbage::hand::Hand;
+/// # use std::str::FromStr;
+/// let rules = ScoringRules { nineteen_bonus : 2, ..ScoringRules::default() };
+/// let h = Hand::from_str("2H 4C 6D 8S | QH").unwrap();
+/// assert_eq!(h.score(), 0);
+/// assert_eq!(h.score_with(&rules, HandKind::Hand), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScoringRules {
+    /// the four cards in a player's hand sharing a suit counts as a
+    /// flush even if the cut does not share that suit
+    pub four_card_flush : bool,
+    /// which flushes count in the crib
+    pub crib_flush : CribFlush,
+    /// points for holding the Jack with the same suit as the cut
+    pub nobs : usize,
+    /// points the dealer pegs when the cut is a Jack
+    pub his_heels : usize,
+    /// points for a hand without any scoring combinations (a "nineteen" hand)
+    ///
+    /// holding nobs is a combination even if the rules make it worth nothing,
+    /// so a hand with nobs never gets the bonus
+    pub nineteen_bonus : usize,
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        Self {
+            four_card_flush : true,
+            crib_flush : CribFlush::FiveCardOnly,
+            nobs : 1,
+            his_heels : 2,
+            nineteen_bonus : 0,
+        }
+    }
+}
+
+impl ScoringRules {
+    /// check if a flush counts for the input kind of hand
+    ///
+    /// `with_cut` is true if the cut shares the suit of the flush
+    #[must_use]
+    pub fn counts_flush(&self, kind : HandKind, with_cut : bool) -> bool {
+        match (kind, self.crib_flush) {
+            (HandKind::Crib, CribFlush::FiveCardOnly) => with_cut,
+            (HandKind::Crib, CribFlush::Never) => false,
+            (HandKind::Hand, _) | (HandKind::Crib, CribFlush::SameAsHand) => with_cut || self.four_card_flush,
+        }
+    }
+
+    /// the points the dealer pegs from the cut card ("his heels")
+    #[must_use]
+    pub fn cut_points(&self, cut : Card) -> usize {
+        if cut.rank == Rank::Jack { self.his_heels } else { 0 }
+    }
+}
+
+/// the points from holding `n` cards of the same rank, two points per pair
+const PAIR_POINTS : [usize; 8] = [0, 0, 2, 6, 12, 20, 30, 42];
+
+/// the number of bits used to count the cards of each rank
+const RANK_BITS : usize = 3;
+
+/// score the fifteens, pairs, and runs in the input cards
+///
+/// This is the part of scoring that doesn't care about suits and it
+/// is done without allocating. The fifteens are counted with a small
+/// subset-sum dynamic program run on each call, adding one card at a
+/// time to the count of how many subsets add up to each total
+/// (ignoring totals past fifteen). The pairs and
+/// runs are found from the number of cards of each rank which are
+/// packed into a single integer.
+#[must_use]
+pub fn score_ranks(cards : &[Card]) -> usize {
+    let mut subsets_summing_to = [0usize; 16];
+    subsets_summing_to[0] = 1;
+    let mut rank_counts : u64 = 0;
+    for c in cards {
+        let v = c.value() as usize;
+        for total in (v..=15).rev() {
+            subsets_summing_to[total] += subsets_summing_to[total - v];
+        }
+        rank_counts += 1 << (RANK_BITS * c.mask());
+    }
+    let mut s = 2 * subsets_summing_to[15];
+
+    // go one past the King so that a run ending on a King is counted
+    let mut run_len = 0;
+    let mut run_combos = 1;
+    for r in 0..=13 {
+        let n = ((rank_counts >> (RANK_BITS * r)) & 0b111) as usize;
+        s += PAIR_POINTS[n];
+        if n > 0 {
+            run_len += 1;
+            run_combos *= n;
+        } else {
+            if run_len > 2 {
+                s += run_len * run_combos;
+            }
+            run_len = 0;
+            run_combos = 1;
+        }
+    }
+    s
+}
+
+/// score a four card hand with its cut without allocating
+///
+/// this gives the same score as [`crate::hand::Hand::score_with`]
+/// but is quite a bit faster which helps when scoring every possible hand
+#[must_use]
+pub fn score_fast(hand : &[Card; 4], cut : Card, rules : &ScoringRules, kind : HandKind) -> usize {
+    let mut s = 0;
+
+    let suit = hand[0].suit;
+    if hand[1].suit == suit && hand[2].suit == suit && hand[3].suit == suit {
+        let with_cut = cut.suit == suit;
+        if rules.counts_flush(kind, with_cut) {
+            s += if with_cut { 5 } else { 4 };
+        }
+    }
+
+    let nobs = hand.iter().any(|c| c.suit == cut.suit && c.rank == Rank::Jack);
+    if nobs {
+        s += rules.nobs;
+    }
+
+    s += score_ranks(&[hand[0], hand[1], hand[2], hand[3], cut]);
+
+    if s == 0 && !nobs {
+        s += rules.nineteen_bonus;
+    }
+    s
+}
+
+/// the fewest cards in a hand that can make a flush
+pub const MIN_FLUSH : usize = 3;
+
+/// score any number of cards held in a hand with an optional cut
+///
+/// This handles hands that aren't the usual four cards, like the five
+/// cards dealt in five-card cribbage or a partial holding while deciding
+/// what to discard. The hand makes a flush if it has at least
+/// [`MIN_FLUSH`] cards all sharing a suit and the rules decide whether
+/// the cut has to join it, just like with four cards. Without a cut,
+/// a flush in the hand is scored as if the cut didn't match and there
+/// is no Jack for nobs.
+///
+/// ```
+/// # use cribbage::score::{score_cards, ScoringRules, HandKind};
+/// # use cribbage::cards;
+/// let rules = ScoringRules::default();
+/// assert_eq!(score_cards(&cards!("5H 5C 5D"), None, &rules, HandKind::Hand), 8);
+/// assert_eq!(score_cards(&cards!("2H 4H 6H"), Some(cards!("8H")[0]), &rules, HandKind::Hand), 4);
+/// assert_eq!(score_cards(&cards!("5H 5C 0C JH 4S"), cards!("QH").pop(), &rules, HandKind::Hand), 18);
+/// ```
+#[must_use]
+pub fn score_cards(cards : &[Card], cut : Option<Card>, rules : &ScoringRules, kind : HandKind) -> usize {
+    let mut s = 0;
+
+    if let Some(first) = cards.first() {
+        if cards.len() >= MIN_FLUSH && cards.iter().all(|c| c.suit == first.suit) {
+            let with_cut = cut.is_some_and(|c| c.suit == first.suit);
+            if rules.counts_flush(kind, with_cut) {
+                s += cards.len() + usize::from(with_cut);
+            }
+        }
+    }
+
+    let nobs = cut.is_some_and(|cut| cards.iter().any(|c| c.suit == cut.suit && c.rank == Rank::Jack));
+    if nobs {
+        s += rules.nobs;
+    }
+
+    if let Some(cut) = cut {
+        // the cut is never more than one extra card so this keeps us
+        //  from allocating to join it with the hand
+        s += match cards {
+            [a, b, c, d] => score_ranks(&[*a, *b, *c, *d, cut]),
+            _ => score_ranks(&cards.iter().copied().chain(std::iter::once(cut)).collect::<Vec<_>>()),
+        };
+    } else {
+        s += score_ranks(cards);
+    }
+
+    if s == 0 && !nobs {
+        s += rules.nineteen_bonus;
+    }
+    s
+}
+
+/// a single way that a group of cards scores points
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Combo {
+    /// cards whose values add up to fifteen, worth 2 points
+    Fifteen(Vec<Card>),
+    /// two cards with the same rank, worth 2 points
+    Pair([Card; 2]),
+    /// three or more cards with consecutive ranks, worth a point per card
+    Run(Vec<Card>),
+    /// cards all of the same suit, worth a point per card
+    Flush(Vec<Card>),
+    /// the Jack in the hand with the same suit as the cut, worth 1 point
+    Nobs(Card),
+    /// a hand without any other points, only worth points under
+    /// some house rules
+    Nineteen,
+}
+
+impl Combo {
+    /// the number of points this combination is worth
+    #[must_use]
+    pub fn points(&self) -> usize {
+        self.points_with(&ScoringRules::default())
+    }
+
+    /// the number of points this combination is worth under the input rules
+    #[must_use]
+    pub fn points_with(&self, rules : &ScoringRules) -> usize {
+        match self {
+            Self::Fifteen(_) | Self::Pair(_) => 2,
+            Self::Run(cards) | Self::Flush(cards) => cards.len(),
+            Self::Nobs(_) => rules.nobs,
+            Self::Nineteen => rules.nineteen_bonus,
+        }
+    }
+
+    /// the cards that make up this combination
+    #[must_use]
+    pub fn cards(&self) -> &[Card] {
+        match self {
+            Self::Fifteen(cards) | Self::Run(cards) | Self::Flush(cards) => cards,
+            Self::Pair(cards) => cards,
+            Self::Nobs(card) => std::slice::from_ref(card),
+            Self::Nineteen => &[],
+        }
+    }
+
+    /// what this combination is called, e.g. "a run of three"
+    #[must_use]
+    pub fn name(&self) -> String {
+        match self {
+            Self::Fifteen(_) => "a fifteen".to_string(),
+            Self::Pair(_) => "a pair".to_string(),
+            Self::Run(cards) => format!("a run of {}", number_name(cards.len())),
+            Self::Flush(cards) => format!("a flush of {}", number_name(cards.len())),
+            Self::Nobs(_) => "nobs".to_string(),
+            Self::Nineteen => "a nineteen hand".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Combo {
+    /// print the name of the combination and the cards in it,
+    /// e.g. "a fifteen: 7H 8C"
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.name())?;
+        for c in self.cards() {
+            write!(f, " {}", c)?;
+        }
+        Ok(())
+    }
+}
+
+/// every combination of cards that scores in a hand
+///
+/// the combinations are listed in the order they are usually counted:
+/// fifteens, pairs, runs, the flush, and then nobs
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScoreBreakdown {
+    combos : Vec<Combo>,
+    rules : ScoringRules,
+}
+
+impl ScoreBreakdown {
+    /// find all of the scoring combinations in a hand with the input cut
+    #[must_use]
+    pub fn new(hand : &[Card; 4], cut : Card, rules : &ScoringRules, kind : HandKind) -> Self {
+        let cards : Vec<Card> = hand.iter().copied().chain(std::iter::once(cut)).collect();
+        let mut combos = Vec::new();
+
+        // each subset of the cards whose values sum to fifteen
+        for n in 2..=cards.len() {
+            combos.extend(cards
+                .iter()
+                .copied()
+                .combinations(n)
+                .filter(|subset| subset.iter().map(|c| c.value()).sum::<i32>() == 15)
+                .map(Combo::Fifteen));
+        }
+
+        // each pair of cards with the same rank
+        combos.extend(cards
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| a.rank == b.rank)
+            .map(|(a, b)| Combo::Pair([*a, *b])));
+
+        // runs are the longest sequences of consecutive ranks, with one run
+        //  for each way of choosing a card from each of the ranks
+        let by_rank = |r : Rank| cards.iter().copied().filter(move |c| c.rank == r);
+        let mut start = Some(Rank::Ace);
+        while let Some(low) = start {
+            let ranks : Vec<Rank> = std::iter::successors(Some(low), |r| r.next())
+                .take_while(|r| by_rank(*r).next().is_some())
+                .collect();
+            if ranks.len() > 2 {
+                combos.extend(ranks
+                    .iter()
+                    .map(|r| by_rank(*r))
+                    .multi_cartesian_product()
+                    .map(Combo::Run));
+            }
+            start = ranks.last().unwrap_or(&low).next();
+        }
+
+        // the four cards in the hand all share a suit, the cut may join in
+        //  and the rules decide if it must
+        if hand.iter().all(|c| c.suit == hand[0].suit) {
+            let with_cut = cut.suit == hand[0].suit;
+            if rules.counts_flush(kind, with_cut) {
+                combos.push(Combo::Flush(if with_cut { cards.clone() } else { hand.to_vec() }));
+            }
+        }
+
+        // the Jack matching the suit of the cut
+        if let Some(jack) = hand.iter().find(|c| c.suit == cut.suit && c.rank == Rank::Jack) {
+            combos.push(Combo::Nobs(*jack));
+        }
+
+        // the bonus is only listed if it is worth something
+        if combos.is_empty() && rules.nineteen_bonus > 0 {
+            combos.push(Combo::Nineteen);
+        }
+
+        Self { combos, rules : *rules }
+    }
+
+    /// the scoring combinations in the order they are counted
+    #[must_use]
+    pub fn combos(&self) -> &[Combo] {
+        &self.combos
+    }
+
+    /// the rules the combinations are worth points under
+    #[must_use]
+    pub const fn rules(&self) -> &ScoringRules {
+        &self.rules
+    }
+
+    /// the total number of points from all of the combinations
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.combos.iter().map(|c| c.points_with(&self.rules)).sum()
+    }
+}
+
+impl fmt::Display for ScoreBreakdown {
+    /// count the hand like a person would aloud,
+    /// e.g. "fifteen two, fifteen four and a pair is six"
+    ///
+    /// a hand without any points is traditionally called "nineteen"
+    /// since it is impossible to score nineteen points
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.combos.is_empty() {
+            return write!(f, "nineteen");
+        }
+        let mut total = 0;
+        for (i, combo) in self.combos.iter().enumerate() {
+            if i + 1 == self.combos.len() && i > 0 {
+                write!(f, " and ")?;
+            } else if i > 0 {
+                write!(f, ", ")?;
+            }
+            total += combo.points_with(&self.rules);
+            match combo {
+                Combo::Fifteen(_) => write!(f, "fifteen {}", number_name(total))?,
+                _ => write!(f, "{} is {}", combo.name(), number_name(total))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// the name of a number as it would be said aloud
+fn number_name(n : usize) -> String {
+    const ONES : [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen",
+        "seventeen", "eighteen", "nineteen"
+    ];
+    const TENS : [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"
+    ];
+    match n {
+        0..=19 => ONES[n].to_string(),
+        20..=99 if n.is_multiple_of(10) => TENS[n / 10].to_string(),
+        20..=99 => format!("{}-{}", TENS[n / 10], ONES[n % 10]),
+        _ => n.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards;
+    use crate::hand::Hand;
+    use std::str::FromStr;
+
+    fn breakdown(h : &str) -> ScoreBreakdown {
+        Hand::from_str(h).unwrap().score_breakdown()
+    }
+
+    #[test]
+    fn count_aloud() {
+        assert_eq!(breakdown("2H 3H 5H 0H | 5C").to_string(),
+            "fifteen two, fifteen four, fifteen six, fifteen eight, a pair is ten and a flush of four is fourteen");
+        assert_eq!(breakdown("3H 5C JH QH | 7H").to_string(), "fifteen two, fifteen four, fifteen six and nobs is seven");
+        assert_eq!(breakdown("2H 4C 6D 8S | QH").to_string(), "nineteen");
+        assert_eq!(breakdown("JH QC KD 2S | AC").to_string(), "a run of three is three");
+    }
+
+    #[test]
+    fn double_run_combos() {
+        let b = breakdown("3H 4D 4C 5C | 7H");
+        let runs : Vec<&Combo> = b.combos().iter().filter(|c| matches!(c, Combo::Run(_))).collect();
+        assert_eq!(runs, vec![&Combo::Run(cards!("3H 4D 5C")), &Combo::Run(cards!("3H 4C 5C"))]);
+        assert_eq!(b.combos().iter().filter(|c| matches!(c, Combo::Pair(_))).count(), 1);
+        assert_eq!(b.total(), 12);
+    }
+
+    #[test]
+    fn combo_display() {
+        assert_eq!(Combo::Fifteen(cards!("7H 8C")).to_string(), "a fifteen: 7H 8C");
+        assert_eq!(Combo::Run(cards!("9H 0C JD")).to_string(), "a run of three: 9H 0C JD");
+        assert_eq!(Combo::Nobs(cards!("JD")[0]).to_string(), "nobs: JD");
+    }
+
+    #[test]
+    fn his_heels() {
+        let rules = ScoringRules::default();
+        assert_eq!(rules.cut_points(cards!("JD")[0]), 2);
+        assert_eq!(rules.cut_points(cards!("QD")[0]), 0);
+    }
+
+    #[test]
+    fn nineteen_bonus_listed() {
+        let rules = ScoringRules { nineteen_bonus : 1, ..ScoringRules::default() };
+        let b = Hand::from_str("2H 4C 6D 8S | QH").unwrap().score_breakdown_with(&rules, HandKind::Hand);
+        assert_eq!(b.combos(), &[Combo::Nineteen]);
+        assert_eq!(b.to_string(), "a nineteen hand is one");
+    }
+
+    #[test]
+    fn nineteen_bonus_not_given_with_worthless_nobs() {
+        let rules = ScoringRules { nobs : 0, nineteen_bonus : 3, ..ScoringRules::default() };
+        let hand = Hand::from_str("JH 2C 4D 8S | 6H").unwrap();
+        let b = hand.score_breakdown_with(&rules, HandKind::Hand);
+        assert_eq!(b.combos(), &[Combo::Nobs(cards!("JH")[0])]);
+        assert_eq!(b.total(), 0);
+        assert_eq!(hand.score_with(&rules, HandKind::Hand), 0);
+        assert_eq!(hand.score_fast_with(&rules, HandKind::Hand), 0);
+        assert_eq!(score_cards(hand.hand(), Some(hand.cut()), &rules, HandKind::Hand), 0);
+    }
+
+    #[test]
+    fn flush_with_cut() {
+        let b = breakdown("2H 4H 6H 8H | 0H");
+        assert_eq!(b.combos().last(), Some(&Combo::Flush(cards!("2H 4H 6H 8H 0H"))));
+        assert_eq!(b.total(), 5);
+    }
+
+    #[test]
+    fn fast_matches_score_all() {
+        // scoring doesn't care which suit is which, so checking each
+        //  canonical hand checks every hand it stands in for, and the
+        //  class sizes show that covers all of them
+        let rules = ScoringRules { nineteen_bonus : 1, four_card_flush : false, ..ScoringRules::default() };
+        let mut n = 0;
+        for (hand, size) in crate::deck::canonical_hands() {
+            assert_eq!(hand.score_fast(), hand.score(), "{}", hand);
+            assert_eq!(hand.score_fast_with(&ScoringRules::default(), HandKind::Crib), hand.score_as(HandKind::Crib), "{}", hand);
+            assert_eq!(hand.score_fast_with(&rules, HandKind::Hand), hand.score_with(&rules, HandKind::Hand), "{}", hand);
+            n += size;
+        }
+        assert_eq!(n, 12_994_800);
+    }
+
+    #[test]
+    #[ignore = "slow, run with `cargo test --release -- --ignored`"]
+    fn fast_matches_score_every_hand() {
+        use crate::cardset::CardSet;
+        let mut n = 0;
+        for kept in CardSet::full().combinations(4) {
+            let cards : Vec<Card> = kept.iter().collect();
+            for cut in kept.complement() {
+                let hand = Hand::from_cards(&cards, cut);
+                assert_eq!(hand.score_fast(), hand.score(), "{}", hand);
+                assert_eq!(hand.score_fast_with(&ScoringRules::default(), HandKind::Crib), hand.score_as(HandKind::Crib), "{}", hand);
+                n += 1;
+            }
+        }
+        assert_eq!(n, 12_994_800);
+    }
+
+    #[test]
+    fn score_any_size() {
+        let rules = ScoringRules::default();
+        let score = |h : &str, cut : Option<&str>| score_cards(&cards!(h), cut.map(|c| cards!(c)[0]), &rules, HandKind::Hand);
+        assert_eq!(score("", None), 0);
+        assert_eq!(score("7H 8C", None), 2);
+        assert_eq!(score("2H 4H", Some("6H")), 0);
+        assert_eq!(score("2H 4H 6H", None), 3);
+        assert_eq!(score("JH 2C 4D", Some("8H")), 1);
+        assert_eq!(score("2H 3H 4H 6H 8H", Some("QH")), 15);
+        assert_eq!(score("5H 5C 5D 5S 0H 0C", None), 24 + 14);
+        assert_eq!(score_cards(&cards!("2H 4H 6H 8H QH"), None, &rules, HandKind::Crib), 0);
+        assert_eq!(score_cards(&cards!("2H 4H 6H 8H QH"), cards!("KH").pop(), &rules, HandKind::Crib), 6);
+    }
+
+    #[test]
+    fn score_cards_matches_score() {
+        let rules = ScoringRules { nineteen_bonus : 1, ..ScoringRules::default() };
+        for (hand, _) in crate::deck::canonical_hands().into_iter().step_by(7) {
+            for kind in [HandKind::Hand, HandKind::Crib] {
+                assert_eq!(score_cards(hand.hand(), Some(hand.cut()), &rules, kind), hand.score_with(&rules, kind), "{}", hand);
+            }
+        }
+    }
+
+    #[test]
+    fn breakdown_matches_score() {
+        for (hand, _) in crate::deck::canonical_hands().into_iter().step_by(97) {
+            assert_eq!(hand.score_breakdown().total(), hand.score(), "{}", hand);
+            assert_eq!(hand.score_breakdown_as(HandKind::Crib).total(), hand.score_as(HandKind::Crib), "{}", hand);
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use crate::deck;
+use crate::score::{ HandKind, ScoringRules };
+use std::fmt;
+
+/// the highest score a hand can have under the standard rules
+pub const MAX_SCORE : usize = 29;
+
+/// how often each score shows up among a group of hands
+///
+/// The counts are kept exactly as integers (indexed by the score) so
+/// distributions can be built up by enumerating hands and compared
+/// against published tables without worrying about rounding.
+/// Two distributions are equal if they have the same count for every
+/// score, no matter how many scores without any hands they hold.
+///
+/// ```
+/// # use cribbage::stats::ScoreDistribution;
+/// let d : ScoreDistribution = [2, 4, 4, 6].into_iter().collect();
+/// assert_eq!(d.count(4), 2);
+/// assert_eq!(d.probability(4), 0.5);
+/// assert_eq!(d.mean(), 4.0);
+/// assert_eq!((d.min(), d.max()), (Some(2), Some(6)));
+/// ```
+#[derive(Debug, Clone, Eq, Default)]
+pub struct ScoreDistribution {
+    counts : Vec<u64>,
+}
+
+impl ScoreDistribution {
+    /// an empty distribution
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { counts : Vec::new() }
+    }
+
+    /// a distribution from the number of times each score shows up,
+    /// the score being the index into `counts`
+    #[must_use]
+    pub fn from_counts(counts : Vec<u64>) -> Self {
+        Self { counts }
+    }
+
+    /// record `n` more hands with the input score
+    pub fn add(&mut self, score : usize, n : u64) {
+        if score >= self.counts.len() {
+            self.counts.resize(score + 1, 0);
+        }
+        self.counts[score] += n;
+    }
+
+    /// the number of hands with each score, indexed by the score
+    ///
+    /// this is only as long as it needs to be to hold the largest
+    /// score that has been added
+    #[must_use]
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// the number of hands with the input score
+    #[must_use]
+    pub fn count(&self, score : usize) -> u64 {
+        self.counts.get(score).copied().unwrap_or(0)
+    }
+
+    /// the number of hands in the distribution
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// the chance that a hand from the distribution has the input score
+    ///
+    /// this is NaN if the distribution is empty
+    #[must_use]
+    pub fn probability(&self, score : usize) -> f64 {
+        self.count(score) as f64 / self.total() as f64
+    }
+
+    /// the average score
+    ///
+    /// this is NaN if the distribution is empty
+    #[must_use]
+    pub fn mean(&self) -> f64 {
+        self.sum(1) as f64 / self.total() as f64
+    }
+
+    /// the variance of the scores, treating the distribution as
+    /// the whole population rather than a sample
+    ///
+    /// this is NaN if the distribution is empty
+    #[must_use]
+    pub fn variance(&self) -> f64 {
+        // keep everything as integers until the end so that the
+        //  variance is as exact as it can be
+        let n = u128::from(self.total());
+        let sum = u128::from(self.sum(1));
+        let sum_sq = u128::from(self.sum(2));
+        (n * sum_sq - sum * sum) as f64 / (n * n) as f64
+    }
+
+    /// the lowest score with any hands
+    #[must_use]
+    pub fn min(&self) -> Option<usize> {
+        self.counts.iter().position(|&n| n > 0)
+    }
+
+    /// the highest score with any hands
+    #[must_use]
+    pub fn max(&self) -> Option<usize> {
+        self.counts.iter().rposition(|&n| n > 0)
+    }
+
+    /// each score that shows up along with how many hands have it,
+    /// from the lowest score to the highest
+    pub fn iter(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.counts.iter().copied().enumerate().filter(|&(_, n)| n > 0)
+    }
+
+    /// the counts without the trailing scores that have no hands
+    fn trimmed(&self) -> &[u64] {
+        &self.counts[..self.max().map_or(0, |m| m + 1)]
+    }
+
+    /// the sum of each score raised to the input power over all hands
+    fn sum(&self, power : u32) -> u64 {
+        self.iter().map(|(s, n)| (s as u64).pow(power) * n).sum()
+    }
+}
+
+impl PartialEq for ScoreDistribution {
+    fn eq(&self, other : &Self) -> bool {
+        self.trimmed() == other.trimmed()
+    }
+}
+
+impl FromIterator<usize> for ScoreDistribution {
+    fn from_iter<I : IntoIterator<Item = usize>>(iter : I) -> Self {
+        let mut d = Self::new();
+        d.extend(iter);
+        d
+    }
+}
+
+impl Extend<usize> for ScoreDistribution {
+    fn extend<I : IntoIterator<Item = usize>>(&mut self, iter : I) {
+        for score in iter {
+            self.add(score, 1);
+        }
+    }
+}
+
+impl fmt::Display for ScoreDistribution {
+    /// print each score with its count and probability, one per line
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (score, n) in self.iter() {
+            writeln!(f, "{:>2}: {} ({:.4})", score, n, self.probability(score))?;
+        }
+        Ok(())
+    }
+}
+
+/// the exact number of hands (out of all 12,994,800) with each score
+///
+/// The hands are scored as the input kind of hand under the input rules.
+/// The counts always go up to at least [`MAX_SCORE`] so that scores no
+/// hand can make, like 19, 25, 26 and 27, show up as zeros.
+///
+/// This goes through the hands up to relabeling the suits
+/// (see [`deck::canonical_hands`]) and weighs each by how many
+/// hands share its form, so it only takes a few seconds.
+#[must_use]
+pub fn score_frequencies(rules : &ScoringRules, kind : HandKind) -> ScoreDistribution {
+    let mut d = ScoreDistribution::from_counts(vec![0; MAX_SCORE + 1]);
+    for (hand, n) in deck::canonical_hands() {
+        d.add(hand.score_fast_with(rules, kind), n as u64);
+    }
+    d
+}
+
+/// the scores no hand can make under the input rules
+#[must_use]
+pub fn impossible_scores(rules : &ScoringRules, kind : HandKind) -> Vec<usize> {
+    let d = score_frequencies(rules, kind);
+    (0..d.counts().len()).filter(|&s| d.count(s) == 0).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the number of hands with each score from
+    /// <https://en.wikipedia.org/wiki/Cribbage_statistics>
+    const HAND_FREQUENCIES : [u64; MAX_SCORE + 1] = [
+        1_009_008, 99_792, 2_813_796, 505_008, 2_855_676, 697_508, 1_800_268, 751_324,
+        1_137_236, 361_224, 388_740, 51_680, 317_340, 19_656, 90_100, 9_168,
+        58_248, 11_196, 2_708, 0, 8_068, 2_496, 444, 356,
+        3_680, 0, 0, 0, 76, 4,
+    ];
+
+    /// the number of cribs with each score, where only
+    /// a flush including the cut counts
+    const CRIB_FREQUENCIES : [u64; MAX_SCORE + 1] = [
+        1_022_208, 99_792, 2_839_800, 508_908, 2_868_960, 703_496, 1_787_176, 755_320,
+        1_118_336, 358_368, 378_240, 43_880, 310_956, 16_548, 88_132, 9_072,
+        57_288, 11_196, 2_264, 0, 7_828, 2_472, 444, 356,
+        3_680, 0, 0, 0, 76, 4,
+    ];
+
+    #[test]
+    fn hand_frequencies() {
+        let d = score_frequencies(&ScoringRules::default(), HandKind::Hand);
+        assert_eq!(d.counts(), &HAND_FREQUENCIES);
+        assert_eq!(d.total(), 12_994_800);
+        assert_eq!(impossible_scores(&ScoringRules::default(), HandKind::Hand), vec![19, 25, 26, 27]);
+    }
+
+    #[test]
+    fn crib_frequencies() {
+        let d = score_frequencies(&ScoringRules::default(), HandKind::Crib);
+        assert_eq!(d.counts(), &CRIB_FREQUENCIES);
+        assert_eq!(d.total(), 12_994_800);
+    }
+
+    #[test]
+    fn empty() {
+        let d = ScoreDistribution::new();
+        assert_eq!(d.total(), 0);
+        assert_eq!((d.min(), d.max()), (None, None));
+        assert!(d.mean().is_nan());
+    }
+
+    #[test]
+    fn equal_ignores_trailing_zeros() {
+        let d : ScoreDistribution = [0, 4].into_iter().collect();
+        assert_eq!(d, ScoreDistribution::from_counts(vec![1, 0, 0, 0, 1, 0, 0]));
+        assert_ne!(d, ScoreDistribution::from_counts(vec![1, 0, 0, 0, 2]));
+        assert_eq!(ScoreDistribution::new(), ScoreDistribution::from_counts(vec![0; 30]));
+    }
+
+    #[test]
+    fn moments() {
+        let mut d = ScoreDistribution::new();
+        d.add(0, 3);
+        d.add(4, 1);
+        assert_eq!(d.counts(), &[3, 0, 0, 0, 1]);
+        assert_eq!(d.mean(), 1.0);
+        assert_eq!(d.variance(), 3.0);
+        assert_eq!(d.iter().collect::<Vec<_>>(), vec![(0, 3), (4, 1)]);
+    }
+}
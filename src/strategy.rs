@@ -1,12 +1,148 @@
 use crate::card::Card;
+use crate::cardset::CardSet;
+use crate::deck;
 use crate::hand::Hand;
+use crate::score::{ score_fast, HandKind, ScoringRules };
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
 use itertools::Itertools;
+use std::cmp::Reverse;
 
-/// the Strategy trait which implements how four cards
-/// out of the input list of cards is chosen to be kept
-trait Strategy {
-    fn choose(&mut self, cards_dealt : &Vec<Card>) -> [Card; 4];
+/// what a player knows about the game when choosing their discard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Context<'a> {
+    /// if the player is the dealer, i.e. the crib is theirs
+    pub dealer : bool,
+    /// the player's current score
+    pub score : usize,
+    /// the current score of the opponent, or of the leading
+    /// opponent if there is more than one
+    pub opponent_score : usize,
+    /// the number of players in the game
+    pub players : usize,
+    /// cards the player has seen other than the ones dealt to them
+    pub known_cards : &'a [Card],
+}
+
+impl Default for Context<'_> {
+    /// the start of a two player game where the player is not the dealer
+    fn default() -> Self {
+        Self {
+            dealer : false,
+            score : 0,
+            opponent_score : 0,
+            players : 2,
+            known_cards : &[],
+        }
+    }
+}
+
+/// the Strategy trait which implements how the cards dealt to
+/// a player are split between the four that are kept and the
+/// rest which go into the crib
+///
+/// the trait is object safe so that different strategies can be
+/// swapped in and out of a simulation as `Box<dyn Strategy>`
+pub trait Strategy {
+    /// choose which of the cards dealt go into the crib
+    ///
+    /// the cards returned must be taken from `cards_dealt` and
+    /// leave four cards to be kept, so with six cards dealt (two
+    /// players) two are returned and with five (three or four
+    /// players) only one is
+    fn choose(&mut self, cards_dealt : &[Card], context : &Context<'_>) -> Vec<Card>;
+}
+
+/// the cards dealt that aren't being kept
+fn rest(cards_dealt : &[Card], kept : &[Card]) -> Vec<Card> {
+    cards_dealt.iter().filter(|c| !kept.contains(c)).copied().collect()
+}
+
+/// every way to split the cards dealt into the four kept
+/// and the rest which go into the crib
+///
+/// with six cards dealt there are 15 ways to split them
+#[must_use]
+pub fn discard_options(cards_dealt : &[Card]) -> Vec<([Card; 4], Vec<Card>)> {
+    cards_dealt
+        .iter()
+        .copied()
+        .combinations(4)
+        .map(|kept| {
+            let crib = rest(cards_dealt, &kept);
+            (kept.try_into().unwrap(), crib)
+        })
+        .collect()
+}
+
+/// the key used to break ties between discards with the same value
+///
+/// this prefers to discard the cards with the lowest [`Card::index`]
+/// so that the choice doesn't depend on the order the cards were dealt
+pub(crate) fn tie_break(crib : &[Card]) -> Reverse<u64> {
+    Reverse(CardSet::from(crib).bits())
+}
+
+/// the total score of the kept cards over every cut not already seen
+///
+/// every discard from the same deal has the same number of cuts,
+/// so comparing totals is the same as comparing averages but exact
+pub(crate) fn hand_total(kept : &[Card; 4], seen : CardSet, rules : &ScoringRules) -> usize {
+    deck::remaining(seen)
+        .iter()
+        .map(|cut| score_fast(kept, cut, rules, HandKind::Hand))
+        .sum()
+}
+
+/// the total score of the crib over every way the rest of it and the cut
+/// could be filled in from the cards not already seen, along with the
+/// number of ways there are to fill it in
+///
+/// the other cards in the crib are treated as if they were picked at
+/// random, which is the same as the opponent discarding randomly
+pub(crate) fn crib_total(discard : &[Card], seen : CardSet, rules : &ScoringRules) -> (u64, u64) {
+    assert!(discard.len() <= 4, "no more than four cards can be discarded into the crib");
+    let unseen = deck::remaining(seen | CardSet::from(discard));
+    let mut total = 0;
+    let mut n = 0;
+    for others in unseen.combinations(4 - discard.len()) {
+        let crib : [Card; 4] = discard.iter().copied().chain(others).collect::<Vec<_>>().try_into().unwrap();
+        for cut in unseen.difference(others) {
+            total += score_fast(&crib, cut, rules, HandKind::Crib) as u64;
+            n += 1;
+        }
+    }
+    (total, n)
+}
+
+/// the average points the discarded cards are worth in the crib,
+/// counted for the dealer and against the pone
+///
+/// The rest of the crib and the cut are filled in with every possible
+/// set of cards not in the discard. This is positive if `dealer` (the
+/// crib is ours) and negative otherwise so it can be added straight to
+/// the value of the hand that is kept.
+///
+/// # Panics
+/// - if more than four cards are discarded
+#[must_use]
+pub fn expected_crib_value(discard : &[Card], dealer : bool) -> f64 {
+    let v = expected_crib_value_with(discard, &[], &ScoringRules::default());
+    if dealer { v } else { -v }
+}
+
+/// the average points the discarded cards are worth in the crib
+/// under the input rules, not counting any of the known cards
+///
+/// unlike [`expected_crib_value`] this is always positive no matter
+/// whose crib it is
+///
+/// # Panics
+/// - if more than four cards are discarded
+#[must_use]
+pub fn expected_crib_value_with(discard : &[Card], known_cards : &[Card], rules : &ScoringRules) -> f64 {
+    let (total, n) = crib_total(discard, CardSet::from(known_cards), rules);
+    total as f64 / n as f64
 }
 
 /// the RandStrat simply randomly chooses four of the cards dealt to
@@ -15,35 +151,522 @@ trait Strategy {
 /// it holds its own RNG so that it can be compared to other strategies
 /// without interfering with the central RNG that will do the dealing
 /// of cards
-struct RandStrat<T : Rng> {
+#[derive(Debug, Clone)]
+pub struct RandStrat<T : Rng> {
     rng : T
 }
 
+impl<T : Rng> RandStrat<T> {
+    /// a random strategy drawing from the input RNG
+    pub fn new(rng : T) -> Self {
+        Self { rng }
+    }
+}
+
 impl<T : Rng> Strategy for RandStrat<T> {
-    fn choose (&mut self, cards_dealt : &Vec<Card>) -> [Card; 4] {
+    fn choose(&mut self, cards_dealt : &[Card], _context : &Context<'_>) -> Vec<Card> {
+        let n = cards_dealt.len().saturating_sub(4);
         cards_dealt
-            .choose_multiple(&mut self.rng, 4)
+            .choose_multiple(&mut self.rng, n)
             .copied()
-            .collect::<Vec<Card>>()
-            .try_into()
-            .unwrap()
+            .collect()
     }
 }
 
 /// attempt to get maximum current score of four cards
-struct MaxCurrentScore {}
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaxCurrentScore;
 
 impl Strategy for MaxCurrentScore {
-    fn choose(&mut self, cards_dealt : &Vec<Card>) -> [Card; 4] {
-        cards_dealt
-            .into_iter()
+    /// # Panics
+    /// - if fewer than four cards are dealt
+    fn choose(&mut self, cards_dealt : &[Card], _context : &Context<'_>) -> Vec<Card> {
+        let kept : Vec<Card> = cards_dealt
+            .iter()
             .combinations(4)
             .max_by_key(|cards| Hand::score_nocut(cards))
-            .unwrap()
+            .expect("at least four cards must be dealt")
+            .into_iter()
+            .copied()
+            .collect();
+        rest(cards_dealt, &kept)
+    }
+}
+
+/// keep the four cards with the best average score over all of the cuts
+///
+/// The cut can be any card that wasn't dealt to the player or is otherwise
+/// known (46 cards in a two player game). This ignores what the discards
+/// are worth in the crib, making it the baseline for comparing discards.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExpectedHandValue {
+    rules : ScoringRules,
+}
+
+impl ExpectedHandValue {
+    /// a strategy scoring hands under the input rules
+    #[must_use]
+    pub const fn new(rules : ScoringRules) -> Self {
+        Self { rules }
+    }
+}
+
+impl Strategy for ExpectedHandValue {
+    /// # Panics
+    /// - if fewer than four cards are dealt
+    fn choose(&mut self, cards_dealt : &[Card], context : &Context<'_>) -> Vec<Card> {
+        let seen = CardSet::from(cards_dealt) | CardSet::from(context.known_cards);
+        discard_options(cards_dealt)
+            .into_iter()
+            .max_by_key(|(kept, crib)| (hand_total(kept, seen, &self.rules), tie_break(crib)))
+            .map(|(_, crib)| crib)
+            .expect("at least four cards must be dealt")
+    }
+}
+
+/// keep the four cards with the best average score of the hand
+/// plus (as the dealer) or minus (as the pone) the average score
+/// of the discards in the crib
+///
+/// The hand is averaged over the cuts like [`ExpectedHandValue`] and
+/// the crib over every cut and the rest of the crib together (see
+/// [`expected_crib_value`]). Everything is kept as integer totals
+/// so that comparing discards is exact and ties are broken the same
+/// way as [`ExpectedHandValue`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExpectedHandAndCribValue {
+    rules : ScoringRules,
+}
+
+impl ExpectedHandAndCribValue {
+    /// a strategy scoring hands and cribs under the input rules
+    #[must_use]
+    pub const fn new(rules : ScoringRules) -> Self {
+        Self { rules }
+    }
+}
+
+impl Strategy for ExpectedHandAndCribValue {
+    /// # Panics
+    /// - if fewer than four cards are dealt
+    fn choose(&mut self, cards_dealt : &[Card], context : &Context<'_>) -> Vec<Card> {
+        let seen = CardSet::from(cards_dealt) | CardSet::from(context.known_cards);
+        let cuts = deck::remaining(seen).len() as i128;
+        discard_options(cards_dealt)
+            .into_iter()
+            .max_by_key(|(kept, crib)| {
+                // the hand's average is hand / cuts and the crib's is crib / n,
+                //  multiplying through by cuts * n (which is the same for each
+                //  discard) lets us compare without rounding
+                let hand = hand_total(kept, seen, &self.rules) as i128;
+                let (crib_total, n) = crib_total(crib, seen, &self.rules);
+                let crib_total = crib_total as i128 * cuts;
+                let hand = hand * n as i128;
+                let value = if context.dealer { hand + crib_total } else { hand - crib_total };
+                (value, tie_break(crib))
+            })
+            .map(|(_, crib)| crib)
+            .expect("at least four cards must be dealt")
+    }
+}
+
+/// how hard [`evaluate_discards`] works at estimating each discard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonteCarloConfig {
+    /// the rules the hands and cribs are scored under
+    pub rules : ScoringRules,
+    /// the fewest deals sampled before stopping early is considered
+    pub min_samples : usize,
+    /// the most deals sampled, even if the best discard isn't clear yet
+    pub max_samples : usize,
+    /// the number of deals sampled between checks for stopping early
+    pub batch : usize,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        Self {
+            rules : ScoringRules::default(),
+            min_samples : 200,
+            max_samples : 20_000,
+            batch : 100,
+        }
+    }
+}
+
+/// the estimated value of one way to discard
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscardEstimate {
+    /// the four cards kept
+    pub kept : [Card; 4],
+    /// the cards that go into the crib
+    pub crib : Vec<Card>,
+    /// the average points of the hand plus (as the dealer) or
+    /// minus (as the pone) the points of the crib
+    pub mean : f64,
+    /// half of the width of the 95% confidence interval around the mean
+    ///
+    /// this is only a true 95% interval when all `max_samples` deals are
+    /// sampled, stopping early happens the first time a check after a batch
+    /// passes so the estimates were more likely to be on a lucky run and
+    /// the interval covers the true value somewhat less often than 95%
+    pub half_width : f64,
+    /// the number of deals sampled
+    pub samples : usize,
+}
+
+impl DiscardEstimate {
+    /// the 95% confidence interval around the mean, with the same caveat
+    /// about stopping early as [`DiscardEstimate::half_width`]
+    #[must_use]
+    pub fn interval(&self) -> (f64, f64) {
+        (self.mean - self.half_width, self.mean + self.half_width)
+    }
+}
+
+/// the z-score for a two-sided 95% confidence interval
+const Z_95 : f64 = 1.96;
+
+/// the z-score with `tail` of the standard normal distribution above it,
+/// for `tail` between 0 and one half
+///
+/// this uses Acklam's rational approximation to the inverse of the
+/// normal distribution, which is good to about nine digits
+fn upper_z(tail : f64) -> f64 {
+    const A : [f64; 6] = [-3.969_683_028_665_376e1, 2.209_460_984_245_205e2, -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2, -3.066_479_806_614_716e1, 2.506_628_277_459_239];
+    const B : [f64; 5] = [-5.447_609_879_822_406e1, 1.615_858_368_580_409e2, -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1, -1.328_068_155_288_572e1];
+    const C : [f64; 6] = [-7.784_894_002_430_293e-3, -3.223_964_580_411_365e-1, -2.400_758_277_161_838,
+        -2.549_732_539_343_734, 4.374_664_141_464_968, 2.938_163_982_698_783];
+    const D : [f64; 4] = [7.784_695_709_041_462e-3, 3.224_671_290_700_398e-1, 2.445_134_137_142_996,
+        3.754_408_661_907_416];
+    let poly = |coefficients : &[f64], x : f64| coefficients.iter().fold(0.0, |acc, c| acc * x + c);
+
+    // the approximation gives the lower quantile, which is the upper one negated
+    if tail < 0.024_25 {
+        let q = (-2.0 * tail.ln()).sqrt();
+        -poly(&C, q) / (poly(&D, q) * q + 1.0)
+    } else {
+        let q = tail - 0.5;
+        let r = q * q;
+        -poly(&A, r) * q / (poly(&B, r) * r + 1.0)
+    }
+}
+
+/// the z-score for showing the best of `options` discards is ahead of
+/// every other one with 95% confidence
+///
+/// comparing the best discard against each of the others is that many
+/// tests, so the 5% chance of being wrong is split between them
+/// (the Bonferroni correction)
+fn separation_z(options : usize) -> f64 {
+    let comparisons = options.saturating_sub(1).max(1);
+    upper_z(0.05 / comparisons as f64 / 2.0)
+}
+
+/// a running mean and variance using Welford's algorithm
+#[derive(Debug, Clone, Copy, Default)]
+struct Running {
+    n : usize,
+    mean : f64,
+    m2 : f64,
+}
+
+impl Running {
+    fn add(&mut self, x : f64) {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    /// half of the width of the 95% confidence interval around the mean
+    fn half_width(&self) -> f64 {
+        self.margin(Z_95)
+    }
+
+    /// half of the width of the confidence interval around the mean
+    /// for the input z-score
+    fn margin(&self, z : f64) -> f64 {
+        if self.n < 2 {
+            return f64::INFINITY;
+        }
+        let variance = self.m2 / (self.n - 1) as f64;
+        z * (variance / self.n as f64).sqrt()
+    }
+}
+
+/// estimate the value of each way to discard by sampling deals
+///
+/// Each sample draws the rest of the crib (as if the opponent discarded
+/// at random) and the cut from the cards not dealt or known, then scores
+/// every discard against that same draw so differences between discards
+/// aren't drowned out by the luck of the draw. For the same reason the
+/// discards are compared by their difference on each draw, and sampling
+/// stops once the best discard's lead over every other discard is above
+/// zero with 95% confidence all told (each comparison is held to a
+/// stricter level to make up for there being several of them), or after
+/// `config.max_samples` deals.
+///
+/// The estimates come back in the same order as [`discard_options`].
+///
+/// # Panics
+/// - if fewer than four or more than eight cards are dealt
+pub fn evaluate_discards<R : Rng>(
+    cards_dealt : &[Card],
+    context : &Context<'_>,
+    config : &MonteCarloConfig,
+    rng : &mut R
+) -> Vec<DiscardEstimate> {
+    let options = discard_options(cards_dealt);
+    assert!(!options.is_empty(), "at least four cards must be dealt");
+    let to_draw = 4 - options[0].1.len();
+    let seen = CardSet::from(cards_dealt) | CardSet::from(context.known_cards);
+    let mut unseen : Vec<Card> = deck::remaining(seen).iter().collect();
+    let mut stats = vec![Running::default(); options.len()];
+    let z = separation_z(options.len());
+
+    // how far the discard currently in the lead is ahead of each of the
+    //  others on each draw, these start over whenever the lead changes
+    let mut leader = 0;
+    let mut leads = vec![Running::default(); options.len()];
+    let mut values = vec![0.0; options.len()];
+
+    let mut samples = 0;
+    while samples < config.max_samples {
+        for _ in 0..config.batch.max(1).min(config.max_samples - samples) {
+            let (drawn, _) = unseen.partial_shuffle(rng, to_draw + 1);
+            let (cut, others) = drawn.split_last().unwrap();
+            for (((kept, crib), stat), value) in options.iter().zip(stats.iter_mut()).zip(values.iter_mut()) {
+                let hand = score_fast(kept, *cut, &config.rules, HandKind::Hand) as f64;
+                let full : [Card; 4] = crib.iter().chain(others.iter()).copied().collect::<Vec<_>>().try_into().unwrap();
+                let crib = score_fast(&full, *cut, &config.rules, HandKind::Crib) as f64;
+                *value = if context.dealer { hand + crib } else { hand - crib };
+                stat.add(*value);
+            }
+            for (lead, value) in leads.iter_mut().zip(&values) {
+                lead.add(values[leader] - value);
+            }
+            samples += 1;
+        }
+        let best = stats
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.mean.total_cmp(&b.mean))
+            .map_or(0, |(i, _)| i);
+        if best != leader {
+            leader = best;
+            leads.fill(Running::default());
+        } else if leads[leader].n >= config.min_samples && separated(&leads, leader, z) {
+            break;
+        }
+    }
+
+    options
+        .into_iter()
+        .zip(stats)
+        .map(|((kept, crib), stat)| DiscardEstimate {
+            kept,
+            crib,
+            mean : stat.mean,
+            half_width : stat.half_width(),
+            samples : stat.n,
+        })
+        .collect()
+}
+
+/// check if the leading discard is ahead of all the others
+///
+/// `leads` holds the leader's lead over each discard draw by draw,
+/// the lower end of the interval for the z-score around each mean
+/// lead has to be above zero
+fn separated(leads : &[Running], leader : usize, z : f64) -> bool {
+    leads
+        .iter()
+        .enumerate()
+        .all(|(j, lead)| j == leader || lead.mean - lead.margin(z) > 0.0)
+}
+
+/// keep the four cards with the best estimated value of the hand and crib
+///
+/// This trades the exactness of [`ExpectedHandAndCribValue`] for speed
+/// by sampling deals with [`evaluate_discards`]. It holds its own
+/// ChaCha8 generator, which unlike [`rand::rngs::StdRng`] is fixed from
+/// one release of `rand` to the next, so a run of choices can be repeated.
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+    config : MonteCarloConfig,
+    rng : ChaCha8Rng,
+}
+
+impl MonteCarlo {
+    /// a strategy sampling as much as the input config allows,
+    /// with a generator seeded from `seed`
+    #[must_use]
+    pub fn new(config : MonteCarloConfig, seed : u64) -> Self {
+        Self { config, rng : ChaCha8Rng::seed_from_u64(seed) }
+    }
+}
+
+impl Strategy for MonteCarlo {
+    /// # Panics
+    /// - if fewer than four or more than eight cards are dealt
+    fn choose(&mut self, cards_dealt : &[Card], context : &Context<'_>) -> Vec<Card> {
+        evaluate_discards(cards_dealt, context, &self.config, &mut self.rng)
             .into_iter()
-            .map(|c| c.clone())
-            .collect::<Vec<Card>>()
-            .try_into()
-            .unwrap()
+            .max_by(|a, b| a.mean.total_cmp(&b.mean).then_with(|| tie_break(&a.crib).cmp(&tie_break(&b.crib))))
+            .map(|e| e.crib)
+            .expect("at least four cards must be dealt")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards;
+
+    #[test]
+    fn strategies_discard_two_of_six() {
+        let dealt = cards!("5H 5C 0C JH 2S 9D");
+        let mut strategies : Vec<Box<dyn Strategy>> = vec![
+            Box::new(RandStrat::new(ChaCha8Rng::seed_from_u64(7))),
+            Box::new(MaxCurrentScore),
+            Box::new(ExpectedHandValue::default()),
+        ];
+        for s in strategies.iter_mut() {
+            let crib = s.choose(&dealt, &Context::default());
+            assert_eq!(crib.len(), 2);
+            assert!(crib.iter().all(|c| dealt.contains(c)));
+            assert_ne!(crib[0], crib[1]);
+        }
+    }
+
+    #[test]
+    fn fifteen_options() {
+        let dealt = cards!("5H 5C 0C JH 2S 9D");
+        let options = discard_options(&dealt);
+        assert_eq!(options.len(), 15);
+        assert!(options.iter().all(|(kept, crib)| crib.len() == 2 && !crib.iter().any(|c| kept.contains(c))));
+    }
+
+    #[test]
+    fn expected_hand_value() {
+        let dealt = cards!("5H 5C 0C JH 2S 9D");
+        let crib = ExpectedHandValue::default().choose(&dealt, &Context::default());
+        assert_eq!(crib, cards!("2S 9D"));
+    }
+
+    #[test]
+    fn expected_hand_value_ignores_deal_order() {
+        // the two black sevens and two red nines are interchangeable,
+        //  so ties have to be broken by the cards themselves
+        let dealt = cards!("7S 7C 9H 9D KH QS");
+        let mut rev = dealt.clone();
+        rev.reverse();
+        let mut s = ExpectedHandValue::default();
+        let mut crib = s.choose(&dealt, &Context::default());
+        let mut crib_rev = s.choose(&rev, &Context::default());
+        crib.sort();
+        crib_rev.sort();
+        assert_eq!(crib, crib_rev);
+    }
+
+    #[test]
+    fn crib_value_sign() {
+        let fives = cards!("5H 5S");
+        let v = expected_crib_value(&fives, true);
+        assert!(v > 8.0, "{}", v);
+        assert_eq!(expected_crib_value(&fives, false), -v);
+        assert!(expected_crib_value(&cards!("KH 9S"), true) < v);
+    }
+
+    #[test]
+    fn crib_aware_keeps_fives_out_of_pone_crib() {
+        // as the dealer the pair of fives goes into our own crib,
+        //  as the pone we can't afford to give them away
+        let dealt = cards!("5H 5S 6D 7C KH QS");
+        let mut s = ExpectedHandAndCribValue::default();
+        let as_dealer = s.choose(&dealt, &Context { dealer : true, ..Context::default() });
+        let as_pone = s.choose(&dealt, &Context::default());
+        assert_ne!(as_dealer, as_pone);
+        assert!(!as_pone.iter().any(|c| c.rank == crate::card::Rank::Five));
+    }
+
+    #[test]
+    fn monte_carlo_is_reproducible() {
+        let dealt = cards!("5H 5S 6D 7C KH QS");
+        let config = MonteCarloConfig { max_samples : 500, ..MonteCarloConfig::default() };
+        let a = evaluate_discards(&dealt, &Context::default(), &config, &mut ChaCha8Rng::seed_from_u64(1));
+        let b = evaluate_discards(&dealt, &Context::default(), &config, &mut ChaCha8Rng::seed_from_u64(1));
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 15);
+    }
+
+    #[test]
+    fn monte_carlo_interval_covers_exact() {
+        let dealt = cards!("5H 5S 6D 7C KH QS");
+        let context = Context { dealer : true, ..Context::default() };
+        let config = MonteCarloConfig { min_samples : 2000, max_samples : 2000, ..MonteCarloConfig::default() };
+        let seen = CardSet::from(&dealt[..]);
+        for e in evaluate_discards(&dealt, &context, &config, &mut ChaCha8Rng::seed_from_u64(5)).iter().step_by(4) {
+            let (crib, n) = crib_total(&e.crib, seen, &config.rules);
+            let exact = hand_total(&e.kept, seen, &config.rules) as f64 / 46.0 + crib as f64 / n as f64;
+            // a little wider than the 95% interval so the test doesn't flake
+            assert!((e.mean - exact).abs() < 1.5 * e.half_width, "{:?} exact {}", e, exact);
+            assert_eq!(e.samples, 2000);
+        }
+    }
+
+    #[test]
+    fn monte_carlo_stops_early() {
+        // keeping the four fives is clearly the best
+        let dealt = cards!("5H 5S 5D 5C KH 2S");
+        let config = MonteCarloConfig::default();
+        let estimates = evaluate_discards(&dealt, &Context::default(), &config, &mut ChaCha8Rng::seed_from_u64(9));
+        assert!(estimates[0].samples < config.max_samples);
+        assert_eq!(MonteCarlo::new(config, 9).choose(&dealt, &Context::default()), cards!("KH 2S"));
+    }
+
+    #[test]
+    fn bonferroni_z_scores() {
+        assert!((upper_z(0.025) - Z_95).abs() < 1e-3);
+        assert!((upper_z(0.5)).abs() < 1e-9);
+        assert!((separation_z(2) - Z_95).abs() < 1e-3);
+        // 14 comparisons for the 15 ways to discard from six cards
+        assert!((separation_z(15) - 2.9137).abs() < 1e-3);
+        assert!((upper_z(1e-6) - 4.7534).abs() < 1e-3);
+    }
+
+    #[test]
+    fn separated_pairs_up_draws() {
+        // the draws swing both discards by far more than the one point
+        //  between them, so only the paired differences can tell them apart
+        let a : Vec<f64> = (0..100).map(|i| f64::from(i % 10 * 3)).collect();
+        let b : Vec<f64> = a.iter().map(|x| x - 1.0).collect();
+        fn running(values : impl Iterator<Item = f64>) -> Running {
+            let mut r = Running::default();
+            values.for_each(|x| r.add(x));
+            r
+        }
+        let (ra, rb) = (running(a.iter().copied()), running(b.iter().copied()));
+        assert!(ra.mean - ra.half_width() < rb.mean + rb.half_width());
+
+        let none = running(a.iter().map(|_| 0.0));
+        assert!(separated(&[none, running(a.iter().zip(&b).map(|(x, y)| x - y))], 0, Z_95));
+        assert!(!separated(&[none, none], 0, Z_95));
+    }
+
+    #[test]
+    #[should_panic(expected = "no more than four cards")]
+    fn crib_value_of_too_many_cards() {
+        let _ = expected_crib_value(&cards!("AH 2H 3H 4H 5H"), true);
+    }
+
+    #[test]
+    fn max_current_score_keeps_fives() {
+        let dealt = cards!("5H 5C 0C JH 2S 9D");
+        let crib = MaxCurrentScore.choose(&dealt, &Context { dealer : true, ..Context::default() });
+        assert_eq!(crib, cards!("2S 9D"));
     }
 }